@@ -61,6 +61,7 @@ pub enum Subcommand {
     Test {
         paths: Vec<PathBuf>,
         test_args: Vec<String>,
+        bless: bool,
     },
     Bench {
         paths: Vec<PathBuf>,
@@ -137,7 +138,11 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`");
 
         // Some subcommands get extra options
         match subcommand.as_str() {
-            "test"  => { opts.optmulti("", "test-args", "extra arguments", "ARGS"); },
+            "test"  => {
+                opts.optmulti("", "test-args", "extra arguments", "ARGS");
+                opts.optflag("", "bless", "overwrite the expected output of UI and MIR-opt \
+                                           tests with the actual output");
+            },
             "bench" => { opts.optmulti("", "test-args", "extra arguments", "ARGS"); },
             "dist"  => { opts.optflag("", "install", "run installer as well"); },
             _ => { },
@@ -202,6 +207,7 @@ Arguments:
         ./x.py test src/test/run-pass
         ./x.py test src/libstd --test-args hash_map
         ./x.py test src/libstd --stage 0
+        ./x.py test src/test/ui --bless
 
     If no arguments are passed then the complete artifacts for that stage are
     compiled and tested.
@@ -260,6 +266,7 @@ Arguments:
                 Subcommand::Test {
                     paths: paths,
                     test_args: matches.opt_strs("test-args"),
+                    bless: matches.opt_present("bless"),
                 }
             }
             "bench" => {
@@ -335,6 +342,13 @@ impl Subcommand {
             _ => Vec::new(),
         }
     }
+
+    pub fn bless(&self) -> bool {
+        match *self {
+            Subcommand::Test { bless, .. } => bless,
+            _ => false,
+        }
+    }
 }
 
 fn split(s: Vec<String>) -> Vec<String> {
@@ -71,6 +71,7 @@ use ty::{Region, Issue32330};
 use ty::error::TypeError;
 use syntax_pos::{Pos, Span};
 use errors::{DiagnosticBuilder, DiagnosticStyledString};
+use errors::catalog::Message;
 
 mod note;
 
@@ -711,7 +712,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                          -> DiagnosticBuilder<'tcx>
     {
         let span = trace.cause.span;
-        let failure_str = trace.cause.as_failure_str();
+        let failure_str = self.tcx.sess.translate(&trace.cause.as_failure_message());
         let mut diag = match trace.cause.code {
             ObligationCauseCode::IfExpressionWithNoElse => {
                 struct_span_err!(self.tcx.sess, span, E0317, "{}", failure_str)
@@ -918,22 +919,47 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
 }
 
 impl<'tcx> ObligationCause<'tcx> {
-    fn as_failure_str(&self) -> &'static str {
+    fn as_failure_message(&self) -> Message {
         use traits::ObligationCauseCode::*;
         match self.code {
-            CompareImplMethodObligation { .. } => "method not compatible with trait",
+            CompareImplMethodObligation { .. } => {
+                diagnostic_message!("typeck-method-not-compatible",
+                                    "method not compatible with trait")
+            }
             MatchExpressionArm { source, .. } => match source {
-                hir::MatchSource::IfLetDesugar{..} => "`if let` arms have incompatible types",
-                _ => "match arms have incompatible types",
+                hir::MatchSource::IfLetDesugar{..} => {
+                    diagnostic_message!("typeck-if-let-arms-incompatible",
+                                        "`if let` arms have incompatible types")
+                }
+                _ => {
+                    diagnostic_message!("typeck-match-arms-incompatible",
+                                        "match arms have incompatible types")
+                }
             },
-            IfExpression => "if and else have incompatible types",
-            IfExpressionWithNoElse => "if may be missing an else clause",
-            EquatePredicate => "equality predicate not satisfied",
-            MainFunctionType => "main function has wrong type",
-            StartFunctionType => "start function has wrong type",
-            IntrinsicType => "intrinsic has wrong type",
-            MethodReceiver => "mismatched method receiver",
-            _ => "mismatched types",
+            IfExpression => {
+                diagnostic_message!("typeck-if-else-incompatible",
+                                    "if and else have incompatible types")
+            }
+            IfExpressionWithNoElse => {
+                diagnostic_message!("typeck-if-missing-else", "if may be missing an else clause")
+            }
+            EquatePredicate => {
+                diagnostic_message!("typeck-equality-not-satisfied",
+                                    "equality predicate not satisfied")
+            }
+            MainFunctionType => {
+                diagnostic_message!("typeck-main-wrong-type", "main function has wrong type")
+            }
+            StartFunctionType => {
+                diagnostic_message!("typeck-start-wrong-type", "start function has wrong type")
+            }
+            IntrinsicType => {
+                diagnostic_message!("typeck-intrinsic-wrong-type", "intrinsic has wrong type")
+            }
+            MethodReceiver => {
+                diagnostic_message!("typeck-mismatched-receiver", "mismatched method receiver")
+            }
+            _ => diagnostic_message!("typeck-mismatched-types", "mismatched types"),
         }
     }
 
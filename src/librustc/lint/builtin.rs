@@ -242,6 +242,24 @@ declare_lint! {
     "detects use of deprecated items"
 }
 
+declare_lint! {
+    pub ARITHMETIC_OVERFLOW,
+    Allow,
+    "detects arithmetic on integers that overflows at runtime"
+}
+
+declare_lint! {
+    pub DOUBLE_LOCK,
+    Warn,
+    "detects locking a lock again while a guard of it is alive"
+}
+
+declare_lint! {
+    pub LOCK_ACROSS_BLOCKING_CALL,
+    Allow,
+    "detects calls that block while a lock guard is alive"
+}
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 #[derive(Copy, Clone)]
@@ -286,7 +304,10 @@ impl LintPass for HardwiredLints {
             LEGACY_IMPORTS,
             LEGACY_CONSTRUCTOR_VISIBILITY,
             MISSING_FRAGMENT_SPECIFIER,
-            DEPRECATED
+            DEPRECATED,
+            ARITHMETIC_OVERFLOW,
+            DOUBLE_LOCK,
+            LOCK_ACROSS_BLOCKING_CALL
         )
     }
 }
@@ -0,0 +1,193 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lint levels read from a file given with `--lint-config`.
+//!
+//! The file is a small subset of TOML. The `[lints]` table sets levels for
+//! the whole crate, and tables like `[lints."net::tcp"]` set them for a
+//! module, given by its path from the crate root, and everything inside it:
+//!
+//! ```toml
+//! [lints]
+//! missing_docs = "deny"
+//! unused = "warn"
+//!
+//! [lints."net::tcp"]
+//! dead_code = "allow"
+//! ```
+//!
+//! Keys are names of lints or lint groups, and values are lint levels.
+//! Levels for the crate rank below those given on the command line, and
+//! levels for a module below the attributes in it.
+
+use lint::Level;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug)]
+pub struct LintConfig {
+    /// The file the levels were read from.
+    pub path: PathBuf,
+
+    /// The levels set for each module, as a path from the crate root, which
+    /// is empty for the crate itself. The levels are in the order given.
+    pub scopes: Vec<(Vec<String>, Vec<(String, Level)>)>,
+}
+
+impl LintConfig {
+    pub fn read(path: &Path) -> Result<LintConfig, String> {
+        let mut src = String::new();
+        File::open(path).and_then(|mut f| f.read_to_string(&mut src)).map_err(|e| {
+            format!("couldn't read lint configuration `{}`: {}", path.display(), e)
+        })?;
+        LintConfig::parse(path, &src)
+    }
+
+    pub fn parse(path: &Path, src: &str) -> Result<LintConfig, String> {
+        let mut config = LintConfig {
+            path: path.to_path_buf(),
+            scopes: vec![],
+        };
+        for (i, line) in src.lines().enumerate() {
+            let error = |msg: &str| format!("{}:{}: {}", path.display(), i + 1, msg);
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err(error("expected `]` at the end of the table header"));
+                }
+                let module = match parse_table(&line[1..line.len() - 1]) {
+                    Some(module) => module,
+                    None => {
+                        return Err(error("expected `[lints]` or `[lints.\"path::to::module\"]`"));
+                    }
+                };
+                config.scopes.push((module, vec![]));
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap().trim();
+            let value = match parts.next() {
+                Some(value) => value.trim(),
+                None => return Err(error("expected `lint = \"level\"`")),
+            };
+            let name = match unquote(key) {
+                Some(name) => name,
+                None if is_bare_key(key) => key,
+                None => return Err(error(&format!("invalid lint name `{}`", key))),
+            };
+            let level = match unquote(value).and_then(Level::from_str) {
+                Some(level) => level,
+                None => {
+                    return Err(error(&format!("unknown lint level `{}`, expected one of \
+                                               \"allow\", \"warn\", \"deny\" or \"forbid\"",
+                                              value)));
+                }
+            };
+            match config.scopes.last_mut() {
+                Some(&mut (_, ref mut levels)) => levels.push((name.replace("-", "_"), level)),
+                None => return Err(error("lint levels must be given in a `[lints]` table")),
+            }
+        }
+        Ok(config)
+    }
+
+    /// The levels set for the module at `path`, or for the crate if it's empty.
+    pub fn levels_for(&self, path: &[String]) -> Vec<&(String, Level)> {
+        self.scopes.iter()
+                   .filter(|&&(ref module, _)| &module[..] == path)
+                   .flat_map(|&(_, ref levels)| levels)
+                   .collect()
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        Some(&s[1..s.len() - 1])
+    } else {
+        None
+    }
+}
+
+fn is_bare_key(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Parses what's between the brackets of a table header into a module path.
+fn parse_table(header: &str) -> Option<Vec<String>> {
+    let header = header.trim();
+    if header == "lints" {
+        return Some(vec![]);
+    }
+    if !header.starts_with("lints.") {
+        return None;
+    }
+    unquote(header["lints.".len()..].trim()).and_then(|path| {
+        let module = path.split("::").map(|name| name.trim().to_string()).collect::<Vec<_>>();
+        if module.iter().all(|name| is_bare_key(name)) {
+            Some(module)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LintConfig;
+    use lint::{Allow, Deny, Warn};
+    use std::path::Path;
+
+    #[test]
+    fn parse_scopes() {
+        let config = LintConfig::parse(Path::new("lints.toml"), r#"
+# The whole crate.
+[lints]
+missing-docs = "deny"   # trailing comment
+"unused" = "warn"
+
+[lints."net::tcp"]
+dead_code = "allow"
+"#).unwrap();
+        let crate_levels = config.levels_for(&[]).into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(crate_levels, vec![("missing_docs".to_string(), Deny),
+                                      ("unused".to_string(), Warn)]);
+        let module = vec!["net".to_string(), "tcp".to_string()];
+        let module_levels = config.levels_for(&module).into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(module_levels, vec![("dead_code".to_string(), Allow)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |src| LintConfig::parse(Path::new("lints.toml"), src).unwrap_err();
+        assert_eq!(parse("dead_code = \"allow\""),
+                   "lints.toml:1: lint levels must be given in a `[lints]` table");
+        assert!(parse("[lints]\ndead_code = \"ignore\"").starts_with("lints.toml:2: unknown"));
+        assert!(parse("[other]").starts_with("lints.toml:1: expected `[lints]`"));
+    }
+}
@@ -29,17 +29,19 @@ use dep_graph::DepNode;
 use middle::privacy::AccessLevels;
 use ty::{self, TyCtxt};
 use session::{config, early_error, Session};
-use lint::{Level, LevelSource, Lint, LintId, LintPass, LintSource};
+use lint::{Level, LevelSource, Lint, LintArray, LintId, LintPass, LintSource};
 use lint::{EarlyLintPassObject, LateLintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
 use rustc_serialize::{Decoder, Decodable, Encoder, Encodable};
+use util::common::duration_to_secs_str;
 use util::nodemap::FxHashMap;
 
 use std::cmp;
 use std::default::Default as StdDefault;
 use std::mem;
 use std::fmt;
+use std::time::{Duration, Instant};
 use syntax::attr;
 use syntax::ast;
 use syntax::symbol::Symbol;
@@ -59,11 +61,11 @@ pub struct LintStore {
     /// added by a plugin.
     lints: Vec<(&'static Lint, bool)>,
 
-    /// Trait objects for each lint pass.
+    /// Trait objects for each lint pass, with the time spent in them.
     /// This is only `None` while iterating over the objects. See the definition
     /// of run_lints.
-    early_passes: Option<Vec<EarlyLintPassObject>>,
-    late_passes: Option<Vec<LateLintPassObject>>,
+    early_passes: Option<Vec<(EarlyLintPassObject, PassTime)>>,
+    late_passes: Option<Vec<(LateLintPassObject, PassTime)>>,
 
     /// Lints indexed by name.
     by_name: FxHashMap<String, TargetLint>,
@@ -137,6 +139,21 @@ pub struct FutureIncompatibleInfo {
     pub reference: &'static str // e.g., a URL for an issue/PR/RFC or error code
 }
 
+/// The time spent in a lint pass, measured under `-Z lint-timings`.
+struct PassTime {
+    from_plugin: bool,
+    time: Duration,
+}
+
+impl PassTime {
+    fn new(from_plugin: bool) -> PassTime {
+        PassTime {
+            from_plugin: from_plugin,
+            time: Duration::new(0, 0),
+        }
+    }
+}
+
 /// The targed of the `by_name` map, which accounts for renaming/deprecation.
 enum TargetLint {
     /// A direct lint target
@@ -202,7 +219,7 @@ impl LintStore {
                                from_plugin: bool,
                                pass: EarlyLintPassObject) {
         self.push_pass(sess, from_plugin, &pass);
-        self.early_passes.as_mut().unwrap().push(pass);
+        self.early_passes.as_mut().unwrap().push((pass, PassTime::new(from_plugin)));
     }
 
     pub fn register_late_pass(&mut self,
@@ -210,7 +227,7 @@ impl LintStore {
                               from_plugin: bool,
                               pass: LateLintPassObject) {
         self.push_pass(sess, from_plugin, &pass);
-        self.late_passes.as_mut().unwrap().push(pass);
+        self.late_passes.as_mut().unwrap().push((pass, PassTime::new(from_plugin)));
     }
 
     // Helper method for register_early/late_pass
@@ -241,6 +258,32 @@ impl LintStore {
         }
     }
 
+    /// Prints the time spent in each early and late lint pass, slowest
+    /// first, for `-Z lint-timings`. Passes are named after their lints.
+    pub fn print_pass_timings(&self) {
+        let early = self.early_passes.as_ref().unwrap().iter().map(|&(ref pass, ref pass_time)| {
+            ("early", pass_name(pass.get_lints(), pass_time.from_plugin), pass_time.time)
+        });
+        let late = self.late_passes.as_ref().unwrap().iter().map(|&(ref pass, ref pass_time)| {
+            ("late", pass_name(pass.get_lints(), pass_time.from_plugin), pass_time.time)
+        });
+        let mut rows = early.chain(late).collect::<Vec<_>>();
+        rows.sort_by(|a, b| b.2.cmp(&a.2));
+
+        let total = rows.iter().fold(Duration::new(0, 0), |total, row| total + row.2);
+        println!("{:>10} {:>7}  {:<5}  {}", "time (s)", "%", "kind", "lint pass");
+        for (kind, name, time) in rows {
+            let percent = if total == Duration::new(0, 0) {
+                0.0
+            } else {
+                nanos(time) * 100.0 / nanos(total)
+            };
+            println!("{:>10} {:>6.1}%  {:<5}  {}",
+                     duration_to_secs_str(time), percent, kind, name);
+        }
+        println!("{:>10} {:>6.1}%  {:<5}  {}", duration_to_secs_str(total), 100.0, "", "total");
+    }
+
     pub fn register_future_incompatible(&mut self,
                                         sess: Option<&Session>,
                                         lints: Vec<FutureIncompatibleInfo>) {
@@ -302,7 +345,111 @@ impl LintStore {
         }
     }
 
+    /// The lints a lint or lint group stands for, none if there is no such
+    /// lint or it has been removed.
+    fn lint_ids(&self, lint_name: &str, sess: &Session) -> Vec<LintId> {
+        match self.find_lint(lint_name, sess, None) {
+            Ok(lint_id) => vec![lint_id],
+            Err(FindLintError::Removed) => vec![],
+            Err(FindLintError::NotFound) => {
+                match self.lint_groups.get(lint_name) {
+                    Some(&(ref lint_ids, _)) => lint_ids.clone(),
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    /// Whether a `-A`, `-W`, `-D` or `-F` flag names `lint` or a group it's in.
+    /// This can't be told from the level's source, as allowed lints have none.
+    fn set_on_command_line(&self, lint: LintId, sess: &Session) -> bool {
+        sess.opts.lint_opts.iter().any(|&(ref name, _)| self.lint_ids(name, sess).contains(&lint))
+    }
+
+    /// Whether `lint` may be at a level other than `Allow` at the node `id`,
+    /// before the lint pass has worked out the levels there: it isn't allowed
+    /// for the crate, or the `--lint-config` file or an attribute on `id` or
+    /// on a node around it gives it, or a group it's in, another level.
+    /// Passes that serve only lints allowed by default use this to skip
+    /// their work.
+    pub fn may_be_enabled<'a, 'tcx>(&self,
+                                    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                    lint: &'static Lint,
+                                    id: ast::NodeId)
+                                    -> bool {
+        if self.lint_cap == Some(Allow) {
+            return false;
+        }
+        let lint_id = LintId::of(lint);
+        if self.get_level_source(lint_id).0 != Allow {
+            return true;
+        }
+
+        let raises_lint = |name: &str, level: Level| {
+            level != Allow && self.lint_ids(name, tcx.sess).contains(&lint_id)
+        };
+        let raised_by_attrs = |attrs: &[ast::Attribute]| {
+            gather_attrs(attrs).into_iter().any(|result| match result {
+                Ok((name, level, _)) => raises_lint(&name.as_str(), level),
+                Err(_) => false,
+            })
+        };
+        if let Some(ref config) = tcx.sess.opts.lint_config {
+            let raised = config.scopes.iter().any(|&(_, ref levels)| {
+                levels.iter().any(|&(ref name, level)| raises_lint(name, level))
+            });
+            if raised {
+                return true;
+            }
+        }
+        let mut id = id;
+        loop {
+            if raised_by_attrs(tcx.hir.attrs(id)) {
+                return true;
+            }
+            let parent = tcx.hir.get_parent_node(id);
+            if parent == id {
+                break;
+            }
+            id = parent;
+        }
+        raised_by_attrs(tcx.hir.krate_attrs())
+    }
+
+    // Checks the names in the `--lint-config` file, and sets the levels it
+    // gives for the whole crate. Those for modules are set as they are
+    // entered, see `with_lint_attrs`.
+    fn process_config_file(&mut self, sess: &Session) {
+        let config = match sess.opts.lint_config {
+            Some(ref config) => config,
+            None => return,
+        };
+
+        for &(_, ref levels) in &config.scopes {
+            for &(ref lint_name, _) in levels {
+                let mut db = match check_lint_name(self, lint_name) {
+                    CheckLintNameResult::Ok => continue,
+                    CheckLintNameResult::Warning(ref msg) => sess.struct_warn(msg),
+                    CheckLintNameResult::NoLint => {
+                        sess.struct_err(&format!("unknown lint: `{}`", lint_name))
+                    }
+                };
+                db.note(&format!("in the lint configuration `{}`", config.path.display()));
+                db.emit();
+            }
+        }
+
+        for &&(ref lint_name, level) in &config.levels_for(&[]) {
+            let source = ConfigFile(Symbol::intern(lint_name));
+            for lint_id in self.lint_ids(lint_name, sess) {
+                self.set_level(lint_id, (level, source));
+            }
+        }
+    }
+
     pub fn process_command_line(&mut self, sess: &Session) {
+        self.process_config_file(sess);
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             check_lint_name_cmdline(sess, self,
                                     &lint_name[..], level);
@@ -362,6 +509,10 @@ pub struct LateContext<'a, 'tcx: 'a> {
     /// levels, this stack keeps track of the previous lint levels of whatever
     /// was modified.
     level_stack: Vec<(LintId, LevelSource)>,
+
+    /// The names of the modules we are in, for the levels given for them in
+    /// the `--lint-config` file.
+    module_path: Vec<String>,
 }
 
 /// Context for lint checking of the AST, after expansion, before lowering to
@@ -380,6 +531,28 @@ pub struct EarlyContext<'a> {
     /// levels, this stack keeps track of the previous lint levels of whatever
     /// was modified.
     level_stack: Vec<(LintId, LevelSource)>,
+
+    /// The names of the modules we are in, for the levels given for them in
+    /// the `--lint-config` file.
+    module_path: Vec<String>,
+}
+
+fn pass_name(lints: LintArray, from_plugin: bool) -> String {
+    let names = lints.iter().map(|lint| lint.name_lower()).collect::<Vec<_>>();
+    let name = match names.len() {
+        0 => "(no lints)".to_string(),
+        1...3 => names.join(", "),
+        n => format!("{}, {} and {} more", names[0], names[1], n - 2),
+    };
+    if from_plugin {
+        format!("{} (plugin)", name)
+    } else {
+        name
+    }
+}
+
+fn nanos(time: Duration) -> f64 {
+    time.as_secs() as f64 * 1_000_000_000.0 + time.subsec_nanos() as f64
 }
 
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
@@ -387,8 +560,16 @@ macro_rules! run_lints { ($cx:expr, $f:ident, $ps:ident, $($args:expr),*) => ({
     // Move the vector of passes out of `$cx` so that we can
     // iterate over it mutably while passing `$cx` to the methods.
     let mut passes = $cx.mut_lints().$ps.take().unwrap();
-    for obj in &mut passes {
-        obj.$f($cx, $($args),*);
+    if $cx.sess().opts.debugging_opts.lint_timings {
+        for &mut (ref mut obj, ref mut pass_time) in &mut passes {
+            let start = Instant::now();
+            obj.$f($cx, $($args),*);
+            pass_time.time += start.elapsed();
+        }
+    } else {
+        for &mut (ref mut obj, _) in &mut passes {
+            obj.$f($cx, $($args),*);
+        }
     }
     $cx.mut_lints().$ps = Some(passes);
 }) }
@@ -493,6 +674,11 @@ pub fn raw_struct_lint<'a, S>(sess: &'a Session,
                                   flag, hyphen_case_lint_name, flag, hyphen_case_flag_val));
             }
         },
+        ConfigFile(config_name) => {
+            let path = sess.opts.lint_config.as_ref().unwrap().path.display();
+            err.note(&format!("`{} = \"{}\"` in the lint configuration `{}`",
+                              config_name, level.as_str(), path));
+        }
         Node(lint_attr_name, src) => {
             def = Some(src);
             if lint_attr_name.as_str() != name {
@@ -526,6 +712,7 @@ pub trait LintContext<'tcx>: Sized {
     fn lints(&self) -> &LintStore;
     fn mut_lints(&mut self) -> &mut LintStore;
     fn level_stack(&mut self) -> &mut Vec<(LintId, LevelSource)>;
+    fn module_path(&mut self) -> &mut Vec<String>;
     fn enter_attrs(&mut self, attrs: &'tcx [ast::Attribute]);
     fn exit_attrs(&mut self, attrs: &'tcx [ast::Attribute]);
 
@@ -627,6 +814,54 @@ pub trait LintContext<'tcx>: Sized {
         self.lookup_and_emit(lint, None as Option<Span>, msg);
     }
 
+    /// Like `with_lint_attrs`, for an item that is the module `module` if
+    /// given. The levels given for that module in the `--lint-config` file
+    /// are merged in before those of its attributes.
+    fn with_item_lint_attrs<F>(&mut self,
+                               module: Option<ast::Name>,
+                               attrs: &'tcx [ast::Attribute],
+                               f: F)
+        where F: FnOnce(&mut Self),
+    {
+        let name = match module {
+            Some(name) => name,
+            None => return self.with_lint_attrs(attrs, f),
+        };
+        self.module_path().push(name.to_string());
+        let path = self.module_path().clone();
+        let levels: Vec<(String, Level)> = match self.sess().opts.lint_config {
+            Some(ref config) => config.levels_for(&path).into_iter().cloned().collect(),
+            None => vec![],
+        };
+
+        let mut pushed = 0;
+        for (lint_name, level) in levels {
+            let source = ConfigFile(Symbol::intern(&lint_name));
+            let lint_ids = self.lints().lint_ids(&lint_name, self.sess());
+            for lint_id in lint_ids {
+                // The command line takes precedence over the file, and only
+                // attributes get to complain about overruling `forbid`.
+                if self.lints().set_on_command_line(lint_id, self.sess()) {
+                    continue;
+                }
+                let (now, now_source) = self.lints().get_level_source(lint_id);
+                if now != level && now != Forbid {
+                    self.level_stack().push((lint_id, (now, now_source)));
+                    pushed += 1;
+                    self.mut_lints().set_level(lint_id, (level, source));
+                }
+            }
+        }
+
+        self.with_lint_attrs(attrs, f);
+
+        for _ in 0..pushed {
+            let (lint, lvlsrc) = self.level_stack().pop().unwrap();
+            self.mut_lints().set_level(lint, lvlsrc);
+        }
+        self.module_path().pop();
+    }
+
     /// Merge the lints specified by any lint attributes into the
     /// current lint context, call the provided function, then reset the
     /// lints in effect to their previous state.
@@ -690,6 +925,10 @@ pub trait LintContext<'tcx>: Sized {
                         LintSource::CommandLine(_) => {
                             diag_builder.note("`forbid` lint level was set on command line")
                         }
+                        LintSource::ConfigFile(_) => {
+                            diag_builder.note("`forbid` lint level was set in the lint \
+                                               configuration")
+                        }
                     }.emit()
                 } else if now != level {
                     let src = self.lints().get_level_source(lint_id).1;
@@ -725,6 +964,7 @@ impl<'a> EarlyContext<'a> {
             krate: krate,
             lints: lint_store,
             level_stack: vec![],
+            module_path: vec![],
         }
     }
 }
@@ -747,6 +987,10 @@ impl<'a, 'tcx> LintContext<'tcx> for LateContext<'a, 'tcx> {
         &mut self.level_stack
     }
 
+    fn module_path(&mut self) -> &mut Vec<String> {
+        &mut self.module_path
+    }
+
     fn enter_attrs(&mut self, attrs: &'tcx [ast::Attribute]) {
         debug!("late context: enter_attrs({:?})", attrs);
         run_lints!(self, enter_lint_attrs, late_passes, attrs);
@@ -776,6 +1020,10 @@ impl<'a> LintContext<'a> for EarlyContext<'a> {
         &mut self.level_stack
     }
 
+    fn module_path(&mut self) -> &mut Vec<String> {
+        &mut self.module_path
+    }
+
     fn enter_attrs(&mut self, attrs: &'a [ast::Attribute]) {
         debug!("early context: enter_attrs({:?})", attrs);
         run_lints!(self, enter_lint_attrs, early_passes, attrs);
@@ -819,7 +1067,11 @@ impl<'a, 'tcx> hir_visit::Visitor<'tcx> for LateContext<'a, 'tcx> {
     }
 
     fn visit_item(&mut self, it: &'tcx hir::Item) {
-        self.with_lint_attrs(&it.attrs, |cx| {
+        let module = match it.node {
+            hir::ItemMod(..) => Some(it.name),
+            _ => None,
+        };
+        self.with_item_lint_attrs(module, &it.attrs, |cx| {
             run_lints!(cx, check_item, late_passes, it);
             hir_visit::walk_item(cx, it);
             run_lints!(cx, check_item_post, late_passes, it);
@@ -981,7 +1233,11 @@ impl<'a, 'tcx> hir_visit::Visitor<'tcx> for LateContext<'a, 'tcx> {
 
 impl<'a> ast_visit::Visitor<'a> for EarlyContext<'a> {
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(&it.attrs, |cx| {
+        let module = match it.node {
+            ast::ItemKind::Mod(..) => Some(it.ident.name),
+            _ => None,
+        };
+        self.with_item_lint_attrs(module, &it.attrs, |cx| {
             run_lints!(cx, check_item, early_passes, it);
             ast_visit::walk_item(cx, it);
             run_lints!(cx, check_item_post, early_passes, it);
@@ -1247,6 +1503,7 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         access_levels: access_levels,
         lints: lint_store,
         level_stack: vec![],
+        module_path: vec![],
     };
 
     // Visit the whole crate.
@@ -1270,6 +1527,10 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         }
     }
 
+    if tcx.sess.opts.debugging_opts.lint_timings {
+        cx.lints.print_pass_timings();
+    }
+
     // Put the lint store back in the session.
     mem::replace(&mut *tcx.sess.lint_store.borrow_mut(), cx.lints);
 }
@@ -345,10 +345,14 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine(Symbol),
+
+    /// Lint level was set in the `--lint-config` file.
+    ConfigFile(Symbol),
 }
 
 pub type LevelSource = (Level, LintSource);
 
 pub mod builtin;
+pub mod config_file;
 mod context;
 mod table;
@@ -599,3 +599,107 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     let mut visitor = DeadVisitor { tcx: tcx, live_symbols: live_symbols };
     intravisit::walk_crate(&mut visitor, krate);
 }
+
+// Collects the items that the crate refers to, whether they are defined in
+// the crate or in one of its dependencies. A use of an item from within the
+// item itself, like a recursive call, doesn't count. This is recorded in the
+// crate metadata under `-Z record-item-uses` to find the `pub` items that no
+// crate of a workspace uses.
+struct UsedItemsVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    tables: &'a ty::TypeckTables<'tcx>,
+    owner: Option<DefId>,
+    used: FxHashSet<DefId>,
+}
+
+impl<'a, 'tcx> UsedItemsVisitor<'a, 'tcx> {
+    fn handle_definition(&mut self, def: Def) {
+        match def {
+            Def::Local(..) | Def::Upvar(..) | Def::TyParam(..) | Def::Label(..) |
+            Def::PrimTy(..) | Def::SelfTy(..) | Def::Err => {}
+            _ => self.insert_def_id(def.def_id()),
+        }
+    }
+
+    fn insert_def_id(&mut self, def_id: DefId) {
+        if Some(def_id) != self.owner {
+            self.used.insert(def_id);
+        }
+    }
+
+    fn with_owner<F: FnOnce(&mut Self)>(&mut self, id: ast::NodeId, f: F) {
+        let old_owner = self.owner;
+        self.owner = Some(self.tcx.hir.local_def_id(id));
+        f(self);
+        self.owner = old_owner;
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for UsedItemsVisitor<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_tables = self.tables;
+        self.tables = self.tcx.body_tables(body);
+        let body = self.tcx.hir.body(body);
+        self.visit_body(body);
+        self.tables = old_tables;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        self.with_owner(item.id, |v| intravisit::walk_item(v, item));
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &'tcx hir::TraitItem) {
+        self.with_owner(trait_item.id, |v| intravisit::walk_trait_item(v, trait_item));
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem) {
+        self.with_owner(impl_item.id, |v| intravisit::walk_impl_item(v, impl_item));
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        match expr.node {
+            hir::ExprPath(ref qpath @ hir::QPath::TypeRelative(..)) => {
+                let def = self.tables.qpath_def(qpath, expr.id);
+                self.handle_definition(def);
+            }
+            hir::ExprMethodCall(..) => {
+                let method_call = ty::MethodCall::expr(expr.id);
+                let def_id = self.tables.method_map[&method_call].def_id;
+                self.insert_def_id(def_id);
+            }
+            _ => ()
+        }
+
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat) {
+        if let PatKind::Path(ref qpath @ hir::QPath::TypeRelative(..)) = pat.node {
+            let def = self.tables.qpath_def(qpath, pat.id);
+            self.handle_definition(def);
+        }
+
+        intravisit::walk_pat(self, pat);
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path, _: ast::NodeId) {
+        self.handle_definition(path.def);
+        intravisit::walk_path(self, path);
+    }
+}
+
+/// The items, local or from other crates, that the local crate uses.
+pub fn used_items<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> FxHashSet<DefId> {
+    let mut visitor = UsedItemsVisitor {
+        tcx: tcx,
+        tables: &ty::TypeckTables::empty(),
+        owner: None,
+        used: FxHashSet(),
+    };
+    tcx.hir.krate().visit_all_item_likes(&mut visitor.as_deep_visitor());
+    visitor.used
+}
@@ -15,6 +15,8 @@ use self::RootUnsafeContext::*;
 use ty::{self, Ty, TyCtxt};
 use ty::MethodCall;
 use lint;
+use hir::def_id::LOCAL_CRATE;
+use util::nodemap::{FxHashMap, NodeSet};
 
 use syntax::ast;
 use syntax_pos::Span;
@@ -55,12 +57,27 @@ struct EffectCheckVisitor<'a, 'tcx: 'a> {
 
     /// Whether we're in an unsafe context.
     unsafe_context: UnsafeContext,
+
+    /// What `-Z unsafe-stats` prints.
+    stats: UnsafeStats,
+}
+
+#[derive(Default)]
+struct UnsafeStats {
+    /// The `unsafe` blocks written by the user.
+    blocks: NodeSet,
+    unsafe_fns: usize,
+    /// The operations that need `unsafe`, by kind.
+    ops: FxHashMap<&'static str, usize>,
 }
 
 impl<'a, 'tcx> EffectCheckVisitor<'a, 'tcx> {
     fn require_unsafe_ext(&mut self, node_id: ast::NodeId, span: Span,
-                          description: &str, is_lint: bool) {
+                          description: &'static str, is_lint: bool) {
         if self.unsafe_context.push_unsafe_count > 0 { return; }
+        if self.unsafe_context.root != SafeContext {
+            *self.stats.ops.entry(description).or_insert(0) += 1;
+        }
         match self.unsafe_context.root {
             SafeContext => {
                 if is_lint {
@@ -82,13 +99,14 @@ impl<'a, 'tcx> EffectCheckVisitor<'a, 'tcx> {
                 // OK, but record this.
                 debug!("effect: recording unsafe block as used: {}", block_id);
                 self.tcx.used_unsafe.borrow_mut().insert(block_id);
+                self.tcx.unsafe_ops.borrow_mut().entry(block_id).or_insert(vec![]).push(node_id);
             }
             UnsafeFn => {}
         }
     }
 
-    fn require_unsafe(&mut self, span: Span, description: &str) {
-        self.require_unsafe_ext(ast::DUMMY_NODE_ID, span, description, false)
+    fn require_unsafe(&mut self, node_id: ast::NodeId, span: Span, description: &'static str) {
+        self.require_unsafe_ext(node_id, span, description, false)
     }
 }
 
@@ -118,6 +136,7 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
 
         let old_unsafe_context = self.unsafe_context;
         if is_unsafe_fn {
+            self.stats.unsafe_fns += 1;
             self.unsafe_context = UnsafeContext::new(UnsafeFn)
         } else if is_item_fn {
             self.unsafe_context = UnsafeContext::new(SafeContext)
@@ -132,6 +151,9 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
         let old_unsafe_context = self.unsafe_context;
         match block.rules {
             hir::UnsafeBlock(source) => {
+                if source == hir::UserProvided {
+                    self.stats.blocks.insert(block.id);
+                }
                 // By default only the outermost `unsafe` block is
                 // "used" and so nested unsafe blocks are pointless
                 // (the inner ones are unnecessary and we actually
@@ -175,7 +197,7 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
                 debug!("effect: method call case, base type is {:?}",
                         base_type);
                 if type_is_unsafe_function(base_type) {
-                    self.require_unsafe(expr.span,
+                    self.require_unsafe(expr.id, expr.span,
                                         "invocation of unsafe method")
                 }
             }
@@ -184,7 +206,7 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
                 debug!("effect: call case, base type is {:?}",
                         base_type);
                 if type_is_unsafe_function(base_type) {
-                    self.require_unsafe(expr.span, "call to unsafe function")
+                    self.require_unsafe(expr.id, expr.span, "call to unsafe function")
                 }
             }
             hir::ExprUnary(hir::UnDeref, ref base) => {
@@ -192,16 +214,16 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
                 debug!("effect: unary case, base type is {:?}",
                         base_type);
                 if let ty::TyRawPtr(_) = base_type.sty {
-                    self.require_unsafe(expr.span, "dereference of raw pointer")
+                    self.require_unsafe(expr.id, expr.span, "dereference of raw pointer")
                 }
             }
             hir::ExprInlineAsm(..) => {
-                self.require_unsafe(expr.span, "use of inline assembly");
+                self.require_unsafe(expr.id, expr.span, "use of inline assembly");
             }
             hir::ExprPath(hir::QPath::Resolved(_, ref path)) => {
                 if let Def::Static(def_id, mutbl) = path.def {
                     if mutbl {
-                        self.require_unsafe(expr.span, "use of mutable static");
+                        self.require_unsafe(expr.id, expr.span, "use of mutable static");
                     } else if match self.tcx.hir.get_if_local(def_id) {
                         Some(hir::map::NodeForeignItem(..)) => true,
                         Some(..) => false,
@@ -214,7 +236,7 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
             hir::ExprField(ref base_expr, field) => {
                 if let ty::TyAdt(adt, ..) = self.tables.expr_ty_adjusted(base_expr).sty {
                     if adt.is_union() {
-                        self.require_unsafe(field.span, "access to union field");
+                        self.require_unsafe(expr.id, field.span, "access to union field");
                     }
                 }
             }
@@ -229,7 +251,7 @@ impl<'a, 'tcx> Visitor<'tcx> for EffectCheckVisitor<'a, 'tcx> {
             if let ty::TyAdt(adt, ..) = self.tables.pat_ty(pat).sty {
                 if adt.is_union() {
                     for field in fields {
-                        self.require_unsafe(field.span, "matching on union field");
+                        self.require_unsafe(pat.id, field.span, "matching on union field");
                     }
                 }
             }
@@ -244,7 +266,27 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         tcx: tcx,
         tables: &ty::TypeckTables::empty(),
         unsafe_context: UnsafeContext::new(SafeContext),
+        stats: UnsafeStats::default(),
     };
 
     tcx.hir.krate().visit_all_item_likes(&mut visitor.as_deep_visitor());
+
+    if tcx.sess.opts.debugging_opts.unsafe_stats {
+        print_stats(tcx, &visitor.stats);
+    }
+}
+
+fn print_stats<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, stats: &UnsafeStats) {
+    let used_unsafe = tcx.used_unsafe.borrow();
+    let unneeded_blocks = stats.blocks.iter().filter(|id| !used_unsafe.contains(id)).count();
+    let mut ops: Vec<_> = stats.ops.iter().collect();
+    ops.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    println!("unsafe code in `{}`:", tcx.crate_name(LOCAL_CRATE));
+    println!("    unsafe blocks: {} ({} unneeded)", stats.blocks.len(), unneeded_blocks);
+    println!("    unsafe functions: {}", stats.unsafe_fns);
+    println!("    unsafe operations: {}", ops.iter().map(|&(_, &n)| n).sum::<usize>());
+    for (description, n) in ops {
+        println!("        {}: {}", description, n);
+    }
 }
@@ -22,6 +22,7 @@ use session::search_paths::SearchPaths;
 use rustc_back::{LinkerFlavor, PanicStrategy};
 use rustc_back::target::Target;
 use lint;
+use lint::config_file::LintConfig;
 use middle::cstore;
 
 use syntax::ast::{self, IntTy, UintTy};
@@ -43,7 +44,7 @@ use std::hash::Hasher;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::iter::FromIterator;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct Config {
     pub target: Target,
@@ -134,6 +135,8 @@ impl OutputType {
 pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json,
+    Sarif,
+    Short(ColorConfig),
 }
 
 impl Default for ErrorOutputType {
@@ -287,6 +290,7 @@ top_level_options!(
         debuginfo: DebugInfoLevel [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED],
         lint_cap: Option<lint::Level> [TRACKED],
+        lint_config: Option<LintConfig> [TRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         // FIXME(mw): We track this for now but it actually doesn't make too
@@ -339,6 +343,22 @@ pub enum PrintRequest {
     RelocationModels,
     CodeModels,
     TargetSpec,
+    SourceFiles,
+    NativeLibs,
+    LinkArgs,
+}
+
+impl PrintRequest {
+    /// Whether the crate has to be expanded and its dependencies loaded to
+    /// answer this request, rather than just parsed.
+    pub fn needs_expansion(&self) -> bool {
+        match *self {
+            PrintRequest::SourceFiles |
+            PrintRequest::NativeLibs |
+            PrintRequest::LinkArgs => true,
+            _ => false,
+        }
+    }
 }
 
 pub enum Input {
@@ -459,6 +479,7 @@ pub fn basic_options() -> Options {
         debuginfo: NoDebugInfo,
         lint_opts: Vec::new(),
         lint_cap: None,
+        lint_config: None,
         describe_lints: false,
         output_types: OutputTypes(BTreeMap::new()),
         search_paths: SearchPaths::new(),
@@ -895,6 +916,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "The output of `-Z time-llvm-passes` will only reflect timings of \
          re-translated modules when used with incremental compilation" )],
         "measure time of each LLVM pass"),
+    lint_timings: bool = (false, parse_bool, [UNTRACKED],
+        "measure time of each lint pass and print them, slowest first"),
     input_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather statistics about the input"),
     trans_stats: bool = (false, parse_bool, [UNTRACKED_WITH_WARNING(true,
@@ -952,6 +975,24 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "treat all errors that occur as bugs"),
     continue_parse_after_error: bool = (false, parse_bool, [TRACKED],
           "attempt to recover from parse errors (experimental)"),
+    apply_suggestions: bool = (false, parse_bool, [UNTRACKED],
+          "rewrite the source files with all machine-applicable suggestions"),
+    error_limit: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "emit at most this many errors, and count the rest"),
+    deduplicate_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+          "emit diagnostics with the same code, primary span and message only once"),
+    terminal_width: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "truncate source lines in diagnostics to this many columns around their spans"),
+    diagnostic_catalog: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "translate diagnostics with the message catalog in this file"),
+    record_item_uses: bool = (false, parse_bool, [TRACKED],
+          "record in the crate metadata the `pub` items of the crate and the items it uses \
+           (executables need `--emit=metadata` to write it out)"),
+    unused_pub: Vec<String> = (Vec::new(), parse_string_push, [UNTRACKED],
+          "report the `pub` items of these crate files, or of those in these directories, \
+           that none of them uses"),
+    unsafe_stats: bool = (false, parse_bool, [UNTRACKED],
+          "print the number of `unsafe` blocks, functions and operations of the crate"),
     incremental: Option<String> = (None, parse_opt_string, [UNTRACKED],
           "enable incremental compilation (experimental)"),
     incremental_cc: bool = (false, parse_bool, [UNTRACKED],
@@ -1226,7 +1267,8 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                               "target-list", "target-cpus", "target-features",
                               "relocation-models", "code-models"];
     if nightly_options::is_nightly_build() {
-        print_opts.push("target-spec-json");
+        print_opts.extend_from_slice(&["target-spec-json", "source-files", "native-libs",
+                                       "link-args"]);
     }
 
     vec![
@@ -1284,7 +1326,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         opt::multi_ubnr("Z", "", "Set internal debugging options", "FLAG"),
         opt::opt_s("", "error-format",
                       "How errors and other messages are produced",
-                      "human|json"),
+                      "human|json|sarif|short"),
         opt::opt_s("", "color", "Configure coloring of output:
                                  auto   = colorize, if output goes to a tty (default);
                                  always = always colorize output;
@@ -1304,6 +1346,8 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
                            `hir` (the HIR), `hir,identified`, or
                            `hir,typed` (HIR with types for each node).",
                           "TYPE"),
+        opt::opt("", "lint-config", "Read the lint levels for the crate and its modules \
+                                    from a file", "PATH"),
 
         // new options here should **not** use the `_ubnr` functions, all new
         // unstable options should use the short variants to indicate that they
@@ -1361,13 +1405,15 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
             Some("human")   => ErrorOutputType::HumanReadable(color),
             Some("json") => ErrorOutputType::Json,
+            Some("sarif") => ErrorOutputType::Sarif,
+            Some("short") => ErrorOutputType::Short(color),
 
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => {
                 early_error(ErrorOutputType::HumanReadable(color),
-                            &format!("argument for --error-format must be human or json (instead \
-                                      was `{}`)",
+                            &format!("argument for --error-format must be human, json, sarif \
+                                      or short (instead was `{}`)",
                                      arg))
             }
         }
@@ -1398,6 +1444,10 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         })
     });
 
+    let lint_config = matches.opt_str("lint-config").map(|path| {
+        LintConfig::read(Path::new(&path)).unwrap_or_else(|e| early_error(error_format, &e))
+    });
+
     let debugging_opts = build_debugging_options(matches, error_format);
 
     let mut output_types = BTreeMap::new();
@@ -1573,6 +1623,12 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
             "code-models" => PrintRequest::CodeModels,
             "target-spec-json" if nightly_options::is_unstable_enabled(matches)
                 => PrintRequest::TargetSpec,
+            "source-files" if nightly_options::is_unstable_enabled(matches)
+                => PrintRequest::SourceFiles,
+            "native-libs" if nightly_options::is_unstable_enabled(matches)
+                => PrintRequest::NativeLibs,
+            "link-args" if nightly_options::is_unstable_enabled(matches)
+                => PrintRequest::LinkArgs,
             req => {
                 early_error(error_format, &format!("unknown print request `{}`", req))
             }
@@ -1611,6 +1667,7 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         debuginfo: debuginfo,
         lint_opts: lint_opts,
         lint_cap: lint_cap,
+        lint_config: lint_config,
         describe_lints: describe_lints,
         output_types: OutputTypes(output_types),
         search_paths: search_paths,
@@ -1762,6 +1819,7 @@ impl fmt::Display for CrateType {
 /// how the hash should be calculated when adding a new commandline argument.
 mod dep_tracking {
     use lint;
+    use lint::config_file::LintConfig;
     use middle::cstore;
     use session::search_paths::{PathKind, SearchPaths};
     use std::collections::BTreeMap;
@@ -1828,6 +1886,14 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(Sanitizer);
     impl_dep_tracking_hash_via_hash!(Option<Sanitizer>);
 
+    // Where the lint configuration was read from makes no difference, only
+    // the levels in it do.
+    impl DepTrackingHash for Option<LintConfig> {
+        fn hash(&self, hasher: &mut DefaultHasher, _: ErrorOutputType) {
+            Hash::hash(&self.as_ref().map(|config| &config.scopes), hasher);
+        }
+    }
+
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(CrateType);
     impl_dep_tracking_hash_for_sortable_vec_of!((String, lint::Level));
@@ -1891,6 +1957,7 @@ mod tests {
     use errors;
     use getopts::{getopts, OptGroup};
     use lint;
+    use lint::config_file::LintConfig;
     use middle::cstore::{self, DummyCrateStore};
     use session::config::{build_configuration, build_session_options_and_crate_config};
     use session::build_session;
@@ -2163,6 +2230,25 @@ mod tests {
         assert_eq!(v2.dep_tracking_hash(), v2.clone().dep_tracking_hash());
     }
 
+    #[test]
+    fn test_lint_config_tracking_hash() {
+        let config = |path: &str, level| LintConfig {
+            path: PathBuf::from(path),
+            scopes: vec![(vec![], vec![(String::from("a"), level)])],
+        };
+        let mut v1 = super::basic_options();
+        let mut v2 = super::basic_options();
+        let mut v3 = super::basic_options();
+
+        v1.lint_config = Some(config("a.toml", lint::Allow));
+        v2.lint_config = Some(config("b.toml", lint::Allow));
+        v3.lint_config = Some(config("a.toml", lint::Deny));
+
+        assert_eq!(v1.dep_tracking_hash(), v2.dep_tracking_hash());
+        assert!(v1.dep_tracking_hash() != v3.dep_tracking_hash());
+        assert!(super::basic_options().dep_tracking_hash() != v1.dep_tracking_hash());
+    }
+
     #[test]
     fn test_search_paths_tracking_hash_different_values() {
         let mut v1 = super::basic_options();
@@ -2481,6 +2567,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.meta_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.lint_timings = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_link_args = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_llvm_passes = true;
@@ -2533,6 +2621,20 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_dir = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.apply_suggestions = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.error_limit = Some(10);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.deduplicate_diagnostics = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.terminal_width = Some(80);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.diagnostic_catalog = Some(String::from("fr.catalog"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.unused_pub = vec![String::from("target/debug/deps")];
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.unsafe_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -2578,5 +2680,9 @@ mod tests {
         opts = reference.clone();
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.record_item_uses = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 }
@@ -19,6 +19,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use session::search_paths::{SearchPaths, PathKind};
+use syntax::codemap::FileLoader;
 use util::fs as rustcfs;
 
 #[derive(Copy, Clone)]
@@ -35,6 +36,7 @@ pub struct FileSearch<'a> {
     pub sysroot: &'a Path,
     pub search_paths: &'a SearchPaths,
     pub triple: &'a str,
+    pub loader: &'a FileLoader,
     pub kind: PathKind,
 }
 
@@ -68,12 +70,10 @@ impl<'a> FileSearch<'a> {
     {
         self.for_each_lib_search_path(|lib_search_path, kind| {
             debug!("searching {}", lib_search_path.display());
-            let files = match fs::read_dir(lib_search_path) {
+            let files = match self.loader.read_dir(lib_search_path) {
                 Ok(files) => files,
                 Err(..) => return,
             };
-            let files = files.filter_map(|p| p.ok().map(|s| s.path()))
-                             .collect::<Vec<_>>();
             fn is_rlib(p: &Path) -> bool {
                 p.extension() == Some("rlib".as_ref())
             }
@@ -101,12 +101,14 @@ impl<'a> FileSearch<'a> {
     pub fn new(sysroot: &'a Path,
                triple: &'a str,
                search_paths: &'a SearchPaths,
+               loader: &'a FileLoader,
                kind: PathKind) -> FileSearch<'a> {
         debug!("using sysroot = {}, triple = {}", sysroot.display(), triple);
         FileSearch {
             sysroot: sysroot,
             search_paths: search_paths,
             triple: triple,
+            loader: loader,
             kind: kind,
         }
     }
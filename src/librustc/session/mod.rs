@@ -25,8 +25,10 @@ use mir::transform as mir_pass;
 
 use syntax::ast::NodeId;
 use errors::{self, DiagnosticBuilder};
+use errors::catalog::{Catalog, Message};
 use errors::emitter::{Emitter, EmitterWriter};
 use syntax::json::JsonEmitter;
+use syntax::sarif::SarifEmitter;
 use syntax::feature_gate;
 use syntax::parse;
 use syntax::parse::ParseSess;
@@ -321,6 +323,11 @@ impl Session {
         &self.parse_sess.span_diagnostic
     }
 
+    /// The text of `msg` in the language of the diagnostic catalog in use.
+    pub fn translate(&self, msg: &Message) -> String {
+        self.diagnostic().translate(msg)
+    }
+
     /// Analogous to calling `.span_note` on the given DiagnosticBuilder, but
     /// deduplicates on lint ID, span, and message for this `Session` if we're
     /// not outputting in JSON mode.
@@ -333,9 +340,9 @@ impl Session {
                                        diag_builder: &'b mut DiagnosticBuilder<'a>,
                                        lint: &'static lint::Lint, span: Span, message: &str) {
         match self.opts.error_format {
-            // when outputting JSON for tool consumption, the tool might want
-            // the duplicates
-            config::ErrorOutputType::Json => {
+            // when outputting JSON or SARIF for tool consumption, the tool
+            // might want the duplicates
+            config::ErrorOutputType::Json | config::ErrorOutputType::Sarif => {
                 diag_builder.span_note(span, &message);
             },
             _ => {
@@ -422,6 +429,7 @@ impl Session {
         filesearch::FileSearch::new(self.sysroot(),
                                     &self.opts.target_triple,
                                     &self.opts.search_paths,
+                                    self.codemap().file_loader(),
                                     kind)
     }
     pub fn host_filesearch(&self, kind: PathKind) -> filesearch::FileSearch {
@@ -429,6 +437,7 @@ impl Session {
             self.sysroot(),
             config::host_triple(),
             &self.opts.search_paths,
+            self.codemap().file_loader(),
             kind)
     }
 
@@ -584,11 +593,23 @@ pub fn build_session_with_codemap(sopts: config::Options,
     let emitter: Box<Emitter> = match (sopts.error_format, emitter_dest) {
         (config::ErrorOutputType::HumanReadable(color_config), None) => {
             Box::new(EmitterWriter::stderr(color_config,
-                                           Some(codemap.clone())))
+                                           Some(codemap.clone()))
+                         .terminal_width(sopts.debugging_opts.terminal_width))
         }
         (config::ErrorOutputType::HumanReadable(_), Some(dst)) => {
             Box::new(EmitterWriter::new(dst,
-                                        Some(codemap.clone())))
+                                        Some(codemap.clone()))
+                         .terminal_width(sopts.debugging_opts.terminal_width))
+        }
+        (config::ErrorOutputType::Short(color_config), None) => {
+            Box::new(EmitterWriter::stderr(color_config,
+                                           Some(codemap.clone()))
+                         .short_message(true))
+        }
+        (config::ErrorOutputType::Short(_), Some(dst)) => {
+            Box::new(EmitterWriter::new(dst,
+                                        Some(codemap.clone()))
+                         .short_message(true))
         }
         (config::ErrorOutputType::Json, None) => {
             Box::new(JsonEmitter::stderr(Some(registry), codemap.clone()))
@@ -596,12 +617,37 @@ pub fn build_session_with_codemap(sopts: config::Options,
         (config::ErrorOutputType::Json, Some(dst)) => {
             Box::new(JsonEmitter::new(dst, Some(registry), codemap.clone()))
         }
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), codemap.clone()))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, Some(registry), codemap.clone()))
+        }
     };
 
     let diagnostic_handler =
         errors::Handler::with_emitter(can_print_warnings,
                                       treat_err_as_bug,
                                       emitter);
+    if sopts.debugging_opts.apply_suggestions {
+        diagnostic_handler.collect_suggestions();
+    }
+    diagnostic_handler.set_error_limit(sopts.debugging_opts.error_limit);
+    diagnostic_handler.set_deduplicate(sopts.debugging_opts.deduplicate_diagnostics);
+
+    // The catalog can also be given in the environment, so that it applies to
+    // all the crates of a build.
+    let catalog_path = sopts.debugging_opts.diagnostic_catalog.clone().or_else(|| {
+        env::var("RUSTC_DIAGNOSTIC_CATALOG").ok().and_then(|path| {
+            if path.is_empty() { None } else { Some(path) }
+        })
+    });
+    if let Some(path) = catalog_path {
+        match Catalog::read(Path::new(&path)) {
+            Ok(catalog) => diagnostic_handler.set_catalog(Some(catalog)),
+            Err(e) => early_error(sopts.error_format, &e),
+        }
+    }
 
     build_session_(sopts,
                    dep_graph,
@@ -780,6 +826,10 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
                                            None))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
+        config::ErrorOutputType::Short(color_config) => {
+            Box::new(EmitterWriter::stderr(color_config, None).short_message(true))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
@@ -793,6 +843,10 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
                                            None))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
+        config::ErrorOutputType::Short(color_config) => {
+            Box::new(EmitterWriter::stderr(color_config, None).short_message(true))
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
@@ -468,6 +468,9 @@ pub struct GlobalCtxt<'tcx> {
     /// present in this set can be warned about.
     pub used_unsafe: RefCell<NodeSet>,
 
+    /// The operations that need `unsafe` in each used unsafe block.
+    pub unsafe_ops: RefCell<NodeMap<Vec<ast::NodeId>>>,
+
     /// Set of nodes which mark locals as mutable which end up getting used at
     /// some point. Local variable definitions not in this set can be warned
     /// about.
@@ -728,6 +731,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             inhabitedness_cache: RefCell::new(FxHashMap()),
             lang_items: lang_items,
             used_unsafe: RefCell::new(NodeSet()),
+            unsafe_ops: RefCell::new(NodeMap()),
             used_mut_nodes: RefCell::new(NodeSet()),
             populated_external_types: RefCell::new(DefIdSet()),
             populated_external_primitive_impls: RefCell::new(DefIdSet()),
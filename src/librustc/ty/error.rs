@@ -67,15 +67,22 @@ pub enum UnconstrainedNumeric {
 impl<'tcx> fmt::Display for TypeError<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::TypeError::*;
-        fn report_maybe_different(f: &mut fmt::Formatter,
+        fn report_maybe_different(f: &mut fmt::Formatter, tcx: TyCtxt,
                                   expected: String, found: String) -> fmt::Result {
             // A naive approach to making sure that we're not reporting silly errors such as:
             // (expected closure, found closure).
-            if expected == found {
-                write!(f, "expected {}, found a different {}", expected, found)
+            let msg = if expected == found {
+                diagnostic_message!("typeck-expected-found-different",
+                                    "expected {expected}, found a different {found}",
+                                    expected = expected,
+                                    found = found)
             } else {
-                write!(f, "expected {}, found {}", expected, found)
-            }
+                diagnostic_message!("typeck-expected-found",
+                                    "expected {expected}, found {found}",
+                                    expected = expected,
+                                    found = found)
+            };
+            write!(f, "{}", tcx.sess.translate(&msg))
         }
 
         match *self {
@@ -125,11 +132,12 @@ impl<'tcx> fmt::Display for TypeError<'tcx> {
                            found bound lifetime parameter {}", br)
             }
             Sorts(values) => ty::tls::with(|tcx| {
-                report_maybe_different(f, values.expected.sort_string(tcx),
+                report_maybe_different(f, tcx, values.expected.sort_string(tcx),
                                        values.found.sort_string(tcx))
             }),
             Traits(values) => ty::tls::with(|tcx| {
                 report_maybe_different(f,
+                                       tcx,
                                        format!("trait `{}`",
                                                tcx.item_path_str(values.expected)),
                                        format!("trait `{}`",
@@ -165,10 +173,10 @@ impl<'tcx> fmt::Display for TypeError<'tcx> {
                        values.expected.ty,
                        values.found.ty)
             }
-            ExistentialMismatch(ref values) => {
-                report_maybe_different(f, format!("trait `{}`", values.expected),
+            ExistentialMismatch(ref values) => ty::tls::with(|tcx| {
+                report_maybe_different(f, tcx, format!("trait `{}`", values.expected),
                                        format!("trait `{}`", values.found))
-            }
+            }),
         }
     }
 }
@@ -431,12 +431,12 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 if new_loan.loan_path.has_fork(&old_loan.loan_path) && common.is_some() {
                     let nl = self.bccx.loan_path_to_string(&common.unwrap());
                     let ol = nl.clone();
-                    let new_loan_msg = format!(" (via `{}`)",
-                                               self.bccx.loan_path_to_string(
-                                                   &new_loan.loan_path));
-                    let old_loan_msg = format!(" (via `{}`)",
-                                               self.bccx.loan_path_to_string(
-                                                   &old_loan.loan_path));
+                    let new_loan_msg = format!(" {}", self.bccx.translate(&diagnostic_message!(
+                        "borrowck-via", "(via `{path}`)",
+                        path = self.bccx.loan_path_to_string(&new_loan.loan_path))));
+                    let old_loan_msg = format!(" {}", self.bccx.translate(&diagnostic_message!(
+                        "borrowck-via", "(via `{path}`)",
+                        path = self.bccx.loan_path_to_string(&old_loan.loan_path))));
                     (nl, ol, new_loan_msg, old_loan_msg)
                 } else {
                     (self.bccx.loan_path_to_string(&new_loan.loan_path),
@@ -447,7 +447,7 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
             };
 
             let ol_pronoun = if new_loan.loan_path == old_loan.loan_path {
-                "it".to_string()
+                self.bccx.translate(&diagnostic_message!("borrowck-it", "it"))
             } else {
                 format!("`{}`", ol)
             };
@@ -465,19 +465,29 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
 
             let mut err = match (new_loan.kind, old_loan.kind) {
                 (ty::MutBorrow, ty::MutBorrow) => {
-                    let mut err = struct_span_err!(self.bccx, new_loan.span, E0499,
-                                                  "cannot borrow `{}`{} as mutable \
-                                                  more than once at a time",
-                                                  nl, new_loan_msg);
+                    let mut err = struct_span_err_translated!(self.bccx, new_loan.span, E0499,
+                        diagnostic_message!("borrowck-mutable-borrow-twice",
+                                            "cannot borrow `{path}`{via} as mutable \
+                                             more than once at a time",
+                                            path = nl.clone(),
+                                            via = new_loan_msg.clone()));
                     err.span_label(
                             old_loan.span,
-                            &format!("first mutable borrow occurs here{}", old_loan_msg));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-first-mutable-borrow",
+                                "first mutable borrow occurs here{via}",
+                                via = old_loan_msg)));
                     err.span_label(
                             new_loan.span,
-                            &format!("second mutable borrow occurs here{}", new_loan_msg));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-second-mutable-borrow",
+                                "second mutable borrow occurs here{via}",
+                                via = new_loan_msg)));
                     err.span_label(
                             previous_end_span,
-                            &format!("first borrow ends here"));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-first-borrow-ends",
+                                "first borrow ends here")));
                     err
                 }
 
@@ -533,29 +543,35 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 }
 
                 (..) => {
-                    let mut err = struct_span_err!(self.bccx, new_loan.span, E0502,
-                                                   "cannot borrow `{}`{} as {} because \
-                                                   {} is also borrowed as {}{}",
-                                                   nl,
-                                                   new_loan_msg,
-                                                   new_loan.kind.to_user_str(),
-                                                   ol_pronoun,
-                                                   old_loan.kind.to_user_str(),
-                                                   old_loan_msg);
+                    let new_kind = self.bccx.borrow_kind_str(new_loan.kind);
+                    let old_kind = self.bccx.borrow_kind_str(old_loan.kind);
+                    let mut err = struct_span_err_translated!(self.bccx, new_loan.span, E0502,
+                        diagnostic_message!("borrowck-conflicting-borrow",
+                                            "cannot borrow `{path}`{via} as {kind} because \
+                                             {other} is also borrowed as {other_kind}{other_via}",
+                                            path = nl.clone(),
+                                            via = new_loan_msg.clone(),
+                                            kind = new_kind.clone(),
+                                            other = ol_pronoun,
+                                            other_kind = old_kind.clone(),
+                                            other_via = old_loan_msg.clone()));
                     err.span_label(
                             new_loan.span,
-                            &format!("{} borrow occurs here{}",
-                                     new_loan.kind.to_user_str(),
-                                     new_loan_msg));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-borrow-occurs", "{kind} borrow occurs here{via}",
+                                kind = new_kind,
+                                via = new_loan_msg)));
                     err.span_label(
                             old_loan.span,
-                            &format!("{} borrow occurs here{}",
-                                     old_loan.kind.to_user_str(),
-                                     old_loan_msg));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-borrow-occurs", "{kind} borrow occurs here{via}",
+                                kind = old_kind.clone(),
+                                via = old_loan_msg)));
                     err.span_label(
                             previous_end_span,
-                            &format!("{} borrow ends here",
-                                     old_loan.kind.to_user_str()));
+                            &self.bccx.translate(&diagnostic_message!(
+                                "borrowck-borrow-ends", "{kind} borrow ends here",
+                                kind = old_kind)));
                     err
                 }
             };
@@ -0,0 +1,363 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `double_lock` and `lock_across_blocking_call` lints, which find a
+//! thread deadlocking with itself: by locking a lock again while a guard of
+//! it is alive, or by calling a function that blocks, like `Receiver::recv`,
+//! while a guard is alive.
+//!
+//! The functions that take a lock are those marked `#[rustc_lock]`, like
+//! `Mutex::lock`, or `#[rustc_shared_lock]` if other shared guards of the
+//! lock may be alive alongside theirs, like `RwLock::read`. Locking again is
+//! only flagged if the guard alive or the one taken is exclusive. Those that
+//! block are marked `#[rustc_blocking]`. What a lock function returns is a
+//! guard of the lock its first argument refers to, and so is what is computed
+//! from a guard by moving it, like the `MutexGuard` that `unwrap` takes out of
+//! a `LockResult`. Two locks are the same if they are the same lvalue, once
+//! the temporaries holding references to it are seen through.
+//!
+//! A guard is alive where the local holding it may be initialized, which is
+//! where drop elaboration keeps its drop: until it's moved or dropped.
+
+use super::dataflow::MaybeInitializedLvals;
+use super::gather_moves::{HasMoveData, MoveData, LookupResult};
+use super::{drop_flag_effects_for_location, MoveDataParamEnv};
+
+use rustc::hir::def_id::DefId;
+use rustc::lint::builtin::{DOUBLE_LOCK, LOCK_ACROSS_BLOCKING_CALL};
+use rustc::mir::{Local, Location, Lvalue, Mir, Operand, ProjectionElem, Rvalue};
+use rustc::mir::{StatementKind, Terminator, TerminatorKind};
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_mir::util::elaborate_drops::DropFlagState;
+use errors::{Diagnostic, Level};
+use syntax::ast;
+use syntax_pos::Span;
+
+/// How a guard holds its lock.
+#[derive(Clone, Copy, PartialEq)]
+enum Access {
+    Shared,
+    Exclusive,
+}
+
+/// A guard of the lock `lock`, taken at `span`.
+#[derive(Clone, PartialEq)]
+struct Guard<'tcx> {
+    lock: Lvalue<'tcx>,
+    access: Access,
+    span: Span,
+}
+
+/// Where a local assigned only once gets its value from, as far as finding
+/// what a reference refers to goes.
+enum Source<'a, 'tcx: 'a> {
+    Ref(&'a Lvalue<'tcx>),
+    Use(&'a Lvalue<'tcx>),
+    /// A call to `Deref::deref` or `DerefMut::deref_mut`.
+    Deref(&'a Lvalue<'tcx>),
+}
+
+struct LockGuards<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    sources: IndexVec<Local, Option<Source<'a, 'tcx>>>,
+}
+
+/// How deep `referent` follows references before giving up.
+const MAX_REFERENT_DEPTH: usize = 16;
+
+pub fn check_lock_guards<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   id: ast::NodeId,
+                                   mir: &Mir<'tcx>) {
+    let takes_locks = mir.basic_blocks().iter().any(|data| {
+        callee(data.terminator()).and_then(|def_id| lock_access(tcx, def_id)).is_some()
+    });
+    if !takes_locks {
+        return;
+    }
+
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+    let cx = LockGuards {
+        tcx: tcx,
+        mir: mir,
+        sources: sources(tcx, mir),
+    };
+    let guards = cx.find_guards(&param_env);
+
+    let move_data = MoveData::gather_moves(mir, tcx, &param_env);
+    let env = MoveDataParamEnv { move_data: move_data, param_env: param_env };
+    let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
+    let flow_inits =
+        super::do_dataflow(tcx, mir, id, &[], &dead_unwinds,
+                           MaybeInitializedLvals::new(tcx, mir, &env),
+                           |bd, p| &bd.move_data().move_paths[p]);
+
+    for (bb, data) in mir.basic_blocks().iter_enumerated() {
+        let args = match data.terminator().kind {
+            TerminatorKind::Call { ref args, .. } => args,
+            _ => continue,
+        };
+        let def_id = match callee(data.terminator()) {
+            Some(def_id) => def_id,
+            None => continue,
+        };
+        let access = lock_access(tcx, def_id);
+        if access.is_none() && !tcx.has_attr(def_id, "rustc_blocking") {
+            continue;
+        }
+
+        let mut live = flow_inits.sets().on_entry_set_for(bb.index()).to_owned();
+        for i in 0..data.statements.len() {
+            let loc = Location { block: bb, statement_index: i };
+            drop_flag_effects_for_location(tcx, mir, &env, loc, |path, state| {
+                match state {
+                    DropFlagState::Present => { live.add(&path); }
+                    DropFlagState::Absent => { live.remove(&path); }
+                }
+            });
+        }
+
+        // The guards alive at the call, apart from those it takes over.
+        let mut alive = guards.iter_enumerated().filter(|&(local, _)| {
+            let moved = args.iter().any(|arg| match *arg {
+                Operand::Consume(ref lvalue) => base_local(lvalue) == Some(local),
+                Operand::Constant(_) => false,
+            });
+            let lvalue = Lvalue::Local(local);
+            !moved && match env.move_data.rev_lookup.find(&lvalue) {
+                LookupResult::Exact(path) => live.contains(&path),
+                LookupResult::Parent(_) => false,
+            }
+        }).flat_map(|(_, guards)| guards);
+
+        let span = data.terminator().source_info.span;
+        if let Some(access) = access {
+            let lock = args.get(0).and_then(|arg| cx.referent_of_operand(arg));
+            let conflicting = alive.find(|guard| {
+                Some(&guard.lock) == lock.as_ref() &&
+                (guard.access == Access::Exclusive || access == Access::Exclusive)
+            });
+            if let Some(guard) = conflicting {
+                let mut diag = Diagnostic::new(Level::Warning,
+                                               "this lock is already held by this thread");
+                diag.set_span(span);
+                diag.span_label(span, &"locking it again deadlocks");
+                diag.span_note(guard.span, "the guard taken here is still alive");
+                tcx.sess.add_lint_diagnostic(DOUBLE_LOCK, id, diag);
+            }
+        } else if let Some(guard) = alive.next() {
+            let mut diag = Diagnostic::new(Level::Warning, "blocking call while a lock is held");
+            diag.set_span(span);
+            diag.span_label(span, &"this can block with the lock held");
+            diag.span_note(guard.span, "the guard taken here is still alive");
+            tcx.sess.add_lint_diagnostic(LOCK_ACROSS_BLOCKING_CALL, id, diag);
+        }
+    }
+}
+
+/// The function a terminator calls, if it's a call to a known function.
+fn callee(terminator: &Terminator) -> Option<DefId> {
+    match terminator.kind {
+        TerminatorKind::Call { func: Operand::Constant(ref constant), .. } => {
+            match constant.ty.sty {
+                ty::TyFnDef(def_id, ..) => Some(def_id),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// How the function `def_id` holds the lock it takes, if it takes one.
+fn lock_access(tcx: TyCtxt, def_id: DefId) -> Option<Access> {
+    if tcx.has_attr(def_id, "rustc_lock") {
+        Some(Access::Exclusive)
+    } else if tcx.has_attr(def_id, "rustc_shared_lock") {
+        Some(Access::Shared)
+    } else {
+        None
+    }
+}
+
+/// The local an lvalue is part of, if it's in one.
+fn base_local(lvalue: &Lvalue) -> Option<Local> {
+    match *lvalue {
+        Lvalue::Local(local) => Some(local),
+        Lvalue::Static(_) => None,
+        Lvalue::Projection(ref proj) => base_local(&proj.base),
+    }
+}
+
+/// Finds where the locals assigned only once get their values from.
+fn sources<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &'a Mir<'tcx>)
+                     -> IndexVec<Local, Option<Source<'a, 'tcx>>> {
+    let mut assignments = IndexVec::from_elem(0, &mir.local_decls);
+    let mut sources: IndexVec<_, _> = mir.local_decls.iter().map(|_| None).collect();
+    for data in mir.basic_blocks() {
+        for stmt in &data.statements {
+            if let StatementKind::Assign(Lvalue::Local(local), ref rvalue) = stmt.kind {
+                assignments[local] += 1;
+                sources[local] = match *rvalue {
+                    Rvalue::Ref(_, _, ref lvalue) => Some(Source::Ref(lvalue)),
+                    Rvalue::Use(Operand::Consume(ref lvalue)) => Some(Source::Use(lvalue)),
+                    _ => None,
+                };
+            }
+        }
+        if let TerminatorKind::Call {
+            ref args, destination: Some((Lvalue::Local(local), _)), ..
+        } = data.terminator().kind {
+            assignments[local] += 1;
+            sources[local] = match (callee(data.terminator()), args.get(0)) {
+                (Some(def_id), Some(&Operand::Consume(ref lvalue))) if is_deref(tcx, def_id) => {
+                    Some(Source::Deref(lvalue))
+                }
+                _ => None,
+            };
+        }
+    }
+    for (local, &n) in assignments.iter_enumerated() {
+        if n != 1 {
+            sources[local] = None;
+        }
+    }
+    sources
+}
+
+fn is_deref(tcx: TyCtxt, def_id: DefId) -> bool {
+    let trait_id = tcx.trait_of_item(def_id);
+    trait_id.is_some() &&
+        (trait_id == tcx.lang_items.deref_trait() || trait_id == tcx.lang_items.deref_mut_trait())
+}
+
+impl<'a, 'tcx> LockGuards<'a, 'tcx> {
+    /// The guards each local may hold.
+    fn find_guards(&self, param_env: &ty::ParameterEnvironment<'tcx>)
+                   -> IndexVec<Local, Vec<Guard<'tcx>>> {
+        let mut guards = IndexVec::from_elem(vec![], &self.mir.local_decls);
+        for data in self.mir.basic_blocks() {
+            if let TerminatorKind::Call {
+                ref args, destination: Some((Lvalue::Local(local), _)), ..
+            } = data.terminator().kind {
+                let access = callee(data.terminator()).and_then(|def_id| {
+                    lock_access(self.tcx, def_id)
+                });
+                if let (Some(access), Some(lock)) =
+                        (access, args.get(0).and_then(|arg| self.referent_of_operand(arg))) {
+                    guards[local].push(Guard {
+                        lock: lock,
+                        access: access,
+                        span: data.terminator().source_info.span,
+                    });
+                }
+            }
+        }
+
+        // Guards move from local to local until no more do.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for data in self.mir.basic_blocks() {
+                for stmt in &data.statements {
+                    if let StatementKind::Assign(Lvalue::Local(local), ref rvalue) = stmt.kind {
+                        match *rvalue {
+                            Rvalue::Use(ref operand) => {
+                                changed |= move_guards(&mut guards, local, operand);
+                            }
+                            Rvalue::Aggregate(_, ref operands) => {
+                                for operand in operands {
+                                    changed |= move_guards(&mut guards, local, operand);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                if let TerminatorKind::Call {
+                    ref args, destination: Some((Lvalue::Local(local), _)), ..
+                } = data.terminator().kind {
+                    let ty = self.mir.local_decls[local].ty;
+                    if ty.needs_drop(self.tcx, param_env) {
+                        for arg in args {
+                            changed |= move_guards(&mut guards, local, arg);
+                        }
+                    }
+                }
+            }
+        }
+        guards
+    }
+
+    /// What the reference `operand` refers to.
+    fn referent_of_operand(&self, operand: &Operand<'tcx>) -> Option<Lvalue<'tcx>> {
+        match *operand {
+            Operand::Consume(ref lvalue) => self.referent(lvalue, 0),
+            Operand::Constant(_) => None,
+        }
+    }
+
+    /// What the reference in `lvalue` refers to, with the temporaries that
+    /// hold references seen through.
+    fn referent(&self, lvalue: &Lvalue<'tcx>, depth: usize) -> Option<Lvalue<'tcx>> {
+        if depth > MAX_REFERENT_DEPTH {
+            return None;
+        }
+        if let Lvalue::Local(local) = *lvalue {
+            match self.sources[local] {
+                Some(Source::Ref(referent)) => return self.normalize(referent, depth + 1),
+                Some(Source::Use(lvalue)) => return self.referent(lvalue, depth + 1),
+                Some(Source::Deref(lvalue)) => {
+                    return self.referent(lvalue, depth + 1).map(|referent| referent.deref());
+                }
+                None => {}
+            }
+        }
+        self.normalize(lvalue, depth + 1).map(|lvalue| lvalue.deref())
+    }
+
+    /// `lvalue`, with its dereferences of temporaries seen through.
+    fn normalize(&self, lvalue: &Lvalue<'tcx>, depth: usize) -> Option<Lvalue<'tcx>> {
+        match *lvalue {
+            Lvalue::Projection(ref proj) => {
+                if let ProjectionElem::Deref = proj.elem {
+                    self.referent(&proj.base, depth)
+                } else {
+                    self.normalize(&proj.base, depth).map(|base| base.elem(proj.elem.clone()))
+                }
+            }
+            _ => Some(lvalue.clone()),
+        }
+    }
+}
+
+/// Gives `local` the guards that `operand` moves, returning whether it has
+/// any new one.
+fn move_guards<'tcx>(guards: &mut IndexVec<Local, Vec<Guard<'tcx>>>,
+                     local: Local,
+                     operand: &Operand<'tcx>)
+                     -> bool {
+    let from = match *operand {
+        Operand::Consume(ref lvalue) => match base_local(lvalue) {
+            Some(from) if from != local => from,
+            _ => return false,
+        },
+        Operand::Constant(_) => return false,
+    };
+    let mut changed = false;
+    for guard in guards[from].clone() {
+        if !guards[local].contains(&guard) {
+            guards[local].push(guard);
+            changed = true;
+        }
+    }
+    changed
+}
@@ -23,6 +23,8 @@ mod abs_domain;
 pub mod elaborate_drops;
 mod dataflow;
 mod gather_moves;
+pub mod lock_guards;
+pub mod overflow;
 // mod graphviz;
 
 use self::dataflow::{BitDenotation};
@@ -0,0 +1,439 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `arithmetic_overflow` lint, which finds arithmetic on integers that
+//! overflows at runtime, like `x + 100` after `let x: u8 = 200`.
+//!
+//! The values of integer locals are tracked through the MIR as ranges, by a
+//! forward dataflow analysis. Unlike the analyses in `dataflow`, its domain
+//! isn't a bit set, so it has its own fixed point loop: each block's entry
+//! state is the hull of the states its predecessors leave, and locals whose
+//! range keeps growing in a loop are given up on. Locals that are borrowed,
+//! or written through a projection, are not tracked at all.
+//!
+//! An operation is reported when the range of its result lies outside its
+//! type, so that it always overflows, or when one end of the range does,
+//! which for ranges built from constants means some path overflows.
+
+use rustc::lint::builtin::ARITHMETIC_OVERFLOW;
+use rustc::middle::const_val::{ConstInt, ConstVal};
+use rustc::mir::{BasicBlock, BinOp, Literal, Local, Lvalue, Mir, Operand};
+use rustc::mir::{ProjectionElem, Rvalue, StatementKind, TerminatorKind, START_BLOCK};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use errors::{Diagnostic, Level};
+use syntax::ast;
+use syntax_pos::Span;
+
+/// After a block has been entered this many times, locals whose range still
+/// grows are given up on, so that loops reach a fixed point.
+const WIDEN_AFTER: usize = 3;
+
+/// The values a local may hold: those from `lo` to `hi`, inclusive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Range {
+    lo: i128,
+    hi: i128,
+}
+
+impl Range {
+    fn exactly(value: i128) -> Range {
+        Range { lo: value, hi: value }
+    }
+
+    fn hull(self, other: Range) -> Range {
+        Range {
+            lo: if self.lo < other.lo { self.lo } else { other.lo },
+            hi: if self.hi > other.hi { self.hi } else { other.hi },
+        }
+    }
+
+    fn contains(self, other: Range) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    fn describe(self) -> String {
+        if self.lo == self.hi {
+            format!("would be {}", self.lo)
+        } else {
+            format!("is between {} and {}", self.lo, self.hi)
+        }
+    }
+}
+
+/// What is known of each local at some point, with `None` for the locals
+/// that may hold any value.
+type State = IndexVec<Local, Option<Range>>;
+
+/// The range of values of an integer type, and its size in bits. 128-bit
+/// integers are left out, as their ranges don't fit in an `i128`.
+fn int_bounds(tcx: TyCtxt, ty: Ty) -> Option<(Range, u32)> {
+    let (signed, bits) = match ty.sty {
+        ty::TyInt(ity) => {
+            let ity = if ity == ast::IntTy::Is { tcx.sess.target.int_type } else { ity };
+            match ity {
+                ast::IntTy::I8 => (true, 8),
+                ast::IntTy::I16 => (true, 16),
+                ast::IntTy::I32 => (true, 32),
+                ast::IntTy::I64 => (true, 64),
+                _ => return None,
+            }
+        }
+        ty::TyUint(uty) => {
+            let uty = if uty == ast::UintTy::Us { tcx.sess.target.uint_type } else { uty };
+            match uty {
+                ast::UintTy::U8 => (false, 8),
+                ast::UintTy::U16 => (false, 16),
+                ast::UintTy::U32 => (false, 32),
+                ast::UintTy::U64 => (false, 64),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let range = if signed {
+        Range { lo: -(1 << (bits - 1)), hi: (1 << (bits - 1)) - 1 }
+    } else {
+        Range { lo: 0, hi: (1 << bits) - 1 }
+    };
+    Some((range, bits))
+}
+
+/// The local an lvalue is part of, if it's in one.
+fn base_local(lvalue: &Lvalue) -> Option<Local> {
+    match *lvalue {
+        Lvalue::Local(local) => Some(local),
+        Lvalue::Static(_) => None,
+        Lvalue::Projection(ref proj) => base_local(&proj.base),
+    }
+}
+
+struct Overflow<'tcx> {
+    span: Span,
+    op: BinOp,
+    ty: Ty<'tcx>,
+    /// The range of the result, or of the shift amount for shifts.
+    range: Range,
+    certain: bool,
+}
+
+struct RangeAnalysis<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    /// The locals whose value can change other than by assigning to them.
+    untracked: IdxSetBuf<Local>,
+}
+
+pub fn check_arithmetic_overflow<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                           id: ast::NodeId,
+                                           mir: &Mir<'tcx>) {
+    let analysis = RangeAnalysis {
+        tcx: tcx,
+        mir: mir,
+        untracked: untracked_locals(mir),
+    };
+    let entry_states = analysis.entry_states();
+
+    let mut overflows = vec![];
+    for (bb, state) in entry_states.into_iter_enumerated() {
+        if let Some(mut state) = state {
+            analysis.apply_block(bb, &mut state, &mut overflows);
+        }
+    }
+
+    for overflow in overflows {
+        let (msg, label) = match overflow.op {
+            BinOp::Add => ("add", "the result"),
+            BinOp::Sub => ("subtract", "the result"),
+            BinOp::Mul => ("multiply", "the result"),
+            BinOp::Shl => ("shift left", "the shift amount"),
+            _ => ("shift right", "the shift amount"),
+        };
+        let mut diag = Diagnostic::new(Level::Warning, if overflow.certain {
+            "this arithmetic operation will overflow"
+        } else {
+            "this arithmetic operation may overflow"
+        });
+        diag.set_span(overflow.span);
+        diag.span_label(overflow.span, &format!("attempt to {} with overflow", msg));
+        let (bounds, bits) = int_bounds(tcx, overflow.ty).unwrap();
+        let holds = match overflow.op {
+            BinOp::Shl | BinOp::Shr => format!("`{}` has {} bits", overflow.ty, bits),
+            _ => format!("`{}` holds values from {} to {}", overflow.ty, bounds.lo, bounds.hi),
+        };
+        diag.note(&format!("{} {}, but {}", label, overflow.range.describe(), holds));
+        tcx.sess.add_lint_diagnostic(ARITHMETIC_OVERFLOW, id, diag);
+    }
+}
+
+/// Finds the locals that are borrowed, or written through a projection.
+fn untracked_locals(mir: &Mir) -> IdxSetBuf<Local> {
+    let mut untracked = IdxSetBuf::new_empty(mir.local_decls.len());
+    {
+        let mut untrack = |lvalue: &Lvalue, whole: bool| {
+            match (lvalue, base_local(lvalue)) {
+                (&Lvalue::Local(_), _) if whole => {}
+                (_, Some(local)) => { untracked.add(&local); }
+                (_, None) => {}
+            }
+        };
+        for data in mir.basic_blocks().iter() {
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::Assign(ref lvalue, ref rvalue) => {
+                        untrack(lvalue, true);
+                        if let Rvalue::Ref(_, _, ref borrowed) = *rvalue {
+                            untrack(borrowed, false);
+                        }
+                    }
+                    StatementKind::SetDiscriminant { ref lvalue, .. } => untrack(lvalue, false),
+                    StatementKind::InlineAsm { ref outputs, .. } => {
+                        for output in outputs {
+                            untrack(output, false);
+                        }
+                    }
+                    StatementKind::StorageLive(_) |
+                    StatementKind::StorageDead(_) |
+                    StatementKind::Nop => {}
+                }
+            }
+            match data.terminator().kind {
+                TerminatorKind::Call { destination: Some((ref lvalue, _)), .. } => {
+                    untrack(lvalue, true);
+                }
+                TerminatorKind::DropAndReplace { ref location, .. } => {
+                    untrack(location, true);
+                }
+                _ => {}
+            }
+        }
+    }
+    untracked
+}
+
+impl<'a, 'tcx> RangeAnalysis<'a, 'tcx> {
+    /// Runs the analysis to a fixed point, giving the state on entry to each
+    /// block, or `None` for the blocks that can't be reached.
+    fn entry_states(&self) -> IndexVec<BasicBlock, Option<State>> {
+        let blocks = self.mir.basic_blocks();
+        let mut entry_states = IndexVec::from_elem_n(None, blocks.len());
+        let mut visits = IndexVec::from_elem_n(0, blocks.len());
+        entry_states[START_BLOCK] = Some(IndexVec::from_elem_n(None, self.mir.local_decls.len()));
+
+        let mut worklist = vec![START_BLOCK];
+        let mut scratch = vec![];
+        while let Some(bb) = worklist.pop() {
+            let mut state = entry_states[bb].clone().unwrap();
+            self.apply_block(bb, &mut state, &mut scratch);
+            scratch.clear();
+
+            let terminator = blocks[bb].terminator();
+            for &succ in terminator.successors().iter() {
+                let mut incoming = state.clone();
+                // A call's destination is only written on the way to its
+                // return block.
+                match terminator.kind {
+                    TerminatorKind::Call { destination: Some((Lvalue::Local(local), target)), .. }
+                        if target == succ => incoming[local] = None,
+                    TerminatorKind::DropAndReplace { location: Lvalue::Local(local), .. } => {
+                        incoming[local] = None;
+                    }
+                    _ => {}
+                }
+
+                visits[succ] += 1;
+                let merged = match entry_states[succ] {
+                    None => incoming,
+                    Some(ref old) => {
+                        let widen = visits[succ] > WIDEN_AFTER;
+                        let merged = join(old, &incoming, widen);
+                        if merged == *old {
+                            continue;
+                        }
+                        merged
+                    }
+                };
+                entry_states[succ] = Some(merged);
+                if !worklist.contains(&succ) {
+                    worklist.push(succ);
+                }
+            }
+        }
+        entry_states
+    }
+
+    fn apply_block(&self, bb: BasicBlock, state: &mut State, overflows: &mut Vec<Overflow<'tcx>>) {
+        for statement in &self.mir[bb].statements {
+            match statement.kind {
+                StatementKind::Assign(ref lvalue, ref rvalue) => {
+                    let range = self.eval_rvalue(state, rvalue, statement.source_info.span,
+                                                 overflows);
+                    if let Lvalue::Local(local) = *lvalue {
+                        state[local] = if self.untracked.contains(&local) { None } else { range };
+                    }
+                }
+                StatementKind::StorageLive(Lvalue::Local(local)) |
+                StatementKind::StorageDead(Lvalue::Local(local)) => state[local] = None,
+                _ => {}
+            }
+        }
+    }
+
+    fn eval_operand(&self, state: &State, operand: &Operand<'tcx>) -> Option<Range> {
+        match *operand {
+            Operand::Consume(Lvalue::Local(local)) => state[local],
+            // The value of a checked operation, whose range is given to the
+            // local holding the pair of it and the overflow flag.
+            Operand::Consume(Lvalue::Projection(ref proj)) => {
+                match (&proj.base, &proj.elem) {
+                    (&Lvalue::Local(local), &ProjectionElem::Field(field, _))
+                        if field.index() == 0 => state[local],
+                    _ => None,
+                }
+            }
+            Operand::Consume(Lvalue::Static(_)) => None,
+            Operand::Constant(ref constant) => {
+                match constant.literal {
+                    Literal::Value { value: ConstVal::Integral(i) } => {
+                        match i {
+                            ConstInt::I128(_) | ConstInt::U128(_) => None,
+                            i => Some(Range::exactly(i.to_u128_unchecked() as i128)),
+                        }
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn eval_rvalue(&self,
+                   state: &State,
+                   rvalue: &Rvalue<'tcx>,
+                   span: Span,
+                   overflows: &mut Vec<Overflow<'tcx>>)
+                   -> Option<Range> {
+        match *rvalue {
+            Rvalue::Use(ref operand) => self.eval_operand(state, operand),
+            Rvalue::Cast(_, ref operand, ty) => {
+                let range = self.eval_operand(state, operand);
+                match (range, int_bounds(self.tcx, ty)) {
+                    (Some(range), Some((bounds, _))) if bounds.contains(range) => Some(range),
+                    _ => None,
+                }
+            }
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                self.eval_binary_op(state, op, lhs, rhs, false, span, overflows)
+            }
+            Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                self.eval_binary_op(state, op, lhs, rhs, true, span, overflows)
+            }
+            _ => None,
+        }
+    }
+
+    fn eval_binary_op(&self,
+                      state: &State,
+                      op: BinOp,
+                      lhs: &Operand<'tcx>,
+                      rhs: &Operand<'tcx>,
+                      checked: bool,
+                      span: Span,
+                      overflows: &mut Vec<Overflow<'tcx>>)
+                      -> Option<Range> {
+        let ty = lhs.ty(self.mir, self.tcx);
+        let (bounds, bits) = match int_bounds(self.tcx, ty) {
+            Some(bounds) => bounds,
+            None => return None,
+        };
+        let (a, b) = (self.eval_operand(state, lhs), self.eval_operand(state, rhs));
+
+        // Operations on constants alone are reported by `const_err`.
+        let constants = match (lhs, rhs) {
+            (&Operand::Constant(_), &Operand::Constant(_)) => true,
+            _ => false,
+        };
+        let mut report = |range: Range, certain: bool| {
+            if !constants {
+                overflows.push(Overflow {
+                    span: span,
+                    op: op,
+                    ty: ty,
+                    range: range,
+                    certain: certain,
+                });
+            }
+        };
+
+        let range = match (op, a, b) {
+            (BinOp::Add, Some(a), Some(b)) => Range {
+                lo: a.lo.saturating_add(b.lo),
+                hi: a.hi.saturating_add(b.hi),
+            },
+            (BinOp::Sub, Some(a), Some(b)) => Range {
+                lo: a.lo.saturating_sub(b.hi),
+                hi: a.hi.saturating_sub(b.lo),
+            },
+            (BinOp::Mul, Some(a), Some(b)) => {
+                let products = [a.lo.saturating_mul(b.lo), a.lo.saturating_mul(b.hi),
+                                a.hi.saturating_mul(b.lo), a.hi.saturating_mul(b.hi)];
+                Range {
+                    lo: *products.iter().min().unwrap(),
+                    hi: *products.iter().max().unwrap(),
+                }
+            }
+            // A shift overflows when the amount isn't less than the number
+            // of bits, whatever is shifted.
+            (BinOp::Shl, _, Some(b)) | (BinOp::Shr, _, Some(b)) => {
+                let valid = Range { lo: 0, hi: bits as i128 - 1 };
+                if b.hi < valid.lo || b.lo > valid.hi {
+                    report(b, true);
+                } else if !valid.contains(b) {
+                    report(b, false);
+                }
+                return None;
+            }
+            _ => return None,
+        };
+
+        if bounds.contains(range) {
+            Some(range)
+        } else if range.hi < bounds.lo || range.lo > bounds.hi {
+            report(range, true);
+            None
+        } else {
+            report(range, false);
+            // Past an overflow check the value is in range, but without one
+            // it may have wrapped around.
+            if checked {
+                Some(Range {
+                    lo: if range.lo < bounds.lo { bounds.lo } else { range.lo },
+                    hi: if range.hi > bounds.hi { bounds.hi } else { range.hi },
+                })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// The state on entry to a block that is reached from both `old` and `new`.
+/// When widening, locals whose range grows are given up on.
+fn join(old: &State, new: &State, widen: bool) -> State {
+    old.iter().zip(new.iter()).map(|(&a, &b)| {
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                let hull = a.hull(b);
+                if widen && hull != a { None } else { Some(hull) }
+            }
+            _ => None,
+        }
+    }).collect()
+}
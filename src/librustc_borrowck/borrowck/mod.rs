@@ -34,6 +34,8 @@ use rustc::middle::expr_use_visitor as euv;
 use rustc::middle::mem_categorization as mc;
 use rustc::middle::mem_categorization::Categorization;
 use rustc::middle::mem_categorization::ImmutabilityBlame;
+use rustc::lint::Lint;
+use rustc::lint::builtin::{ARITHMETIC_OVERFLOW, DOUBLE_LOCK, LOCK_ACROSS_BLOCKING_CALL};
 use rustc::middle::region;
 use rustc::ty::{self, TyCtxt};
 use rustc::ty::maps::Providers;
@@ -43,7 +45,8 @@ use std::rc::Rc;
 use std::hash::{Hash, Hasher};
 use syntax::ast;
 use syntax_pos::{DUMMY_SP, MultiSpan, Span};
-use errors::DiagnosticBuilder;
+use errors::{Applicability, DiagnosticBuilder};
+use errors::catalog::Message;
 
 use rustc::hir;
 use rustc::hir::intravisit::{self, Visitor};
@@ -95,11 +98,18 @@ fn borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, owner_def_id: DefId) {
     };
 
     let body = bccx.tcx.hir.body(body_id);
+    let errors_before = bccx.tcx.sess.err_count();
 
     if bccx.tcx.has_attr(owner_def_id, "rustc_mir_borrowck") {
         mir::borrowck_mir(bccx, owner_id, &attributes);
     }
 
+    if FnLikeNode::from_node(bccx.tcx.hir.get(owner_id)).is_some() &&
+       lints_may_be_enabled(bccx.tcx, &[ARITHMETIC_OVERFLOW], owner_id) {
+        let mir = bccx.tcx.item_mir(owner_def_id);
+        mir::overflow::check_arithmetic_overflow(bccx.tcx, owner_id, &mir);
+    }
+
     let cfg = cfg::CFG::new(bccx.tcx, &body);
     let AnalysisData { all_loans,
                        loans: loan_dfcx,
@@ -114,6 +124,25 @@ fn borrowck<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, owner_def_id: DefId) {
                                                  owner_id);
 
     check_loans::check_loans(bccx, &loan_dfcx, &flowed_moves, &all_loans, body);
+
+    // Gathering the moves of the MIR needs this body to have been
+    // borrow-checked without errors, as it can't make sense of moves out of
+    // borrows.
+    if FnLikeNode::from_node(bccx.tcx.hir.get(owner_id)).is_some() &&
+       bccx.tcx.sess.err_count() == errors_before &&
+       lints_may_be_enabled(bccx.tcx, &[DOUBLE_LOCK, LOCK_ACROSS_BLOCKING_CALL], owner_id) {
+        let mir = bccx.tcx.item_mir(owner_def_id);
+        mir::lock_guards::check_lock_guards(bccx.tcx, owner_id, &mir);
+    }
+}
+
+// The MIR lints checked here are costly, and most are allowed by default.
+fn lints_may_be_enabled<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                  lints: &[&'static Lint],
+                                  id: ast::NodeId)
+                                  -> bool {
+    let store = tcx.sess.lint_store.borrow();
+    lints.iter().any(|&lint| store.may_be_enabled(tcx, lint, id))
 }
 
 fn build_borrowck_dataflow_data<'a, 'tcx>(this: &mut BorrowckCtxt<'a, 'tcx>,
@@ -506,9 +535,9 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                                      the_move: &move_data::Move,
                                      moved_lp: &LoanPath<'tcx>,
                                      _param_env: &ty::ParameterEnvironment<'tcx>) {
-        let (verb, verb_participle) = match use_kind {
-            MovedInUse => ("use", "used"),
-            MovedInCapture => ("capture", "captured"),
+        let verb = match use_kind {
+            MovedInUse => "use",
+            MovedInCapture => "capture",
         };
 
         let (_ol, _moved_lp_msg, mut err) = match the_move.kind {
@@ -555,54 +584,103 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                     };
 
                 let partial = moved_lp.depth() > lp.depth();
-                let msg = if !has_fork && partial { "partially " }
-                          else if has_fork && !has_common { "collaterally "}
-                          else { "" };
-                let err = struct_span_err!(
-                    self.tcx.sess, use_span, E0382,
-                    "{} of {}moved value: `{}`",
-                    verb, msg, nl);
+                let msg = match (use_kind, !has_fork && partial, has_fork && !has_common) {
+                    (MovedInUse, true, _) => {
+                        diagnostic_message!("borrowck-use-of-partially-moved",
+                                            "use of partially moved value: `{path}`",
+                                            path = nl)
+                    }
+                    (MovedInUse, _, true) => {
+                        diagnostic_message!("borrowck-use-of-collaterally-moved",
+                                            "use of collaterally moved value: `{path}`",
+                                            path = nl)
+                    }
+                    (MovedInUse, _, _) => {
+                        diagnostic_message!("borrowck-use-of-moved",
+                                            "use of moved value: `{path}`",
+                                            path = nl)
+                    }
+                    (MovedInCapture, true, _) => {
+                        diagnostic_message!("borrowck-capture-of-partially-moved",
+                                            "capture of partially moved value: `{path}`",
+                                            path = nl)
+                    }
+                    (MovedInCapture, _, true) => {
+                        diagnostic_message!("borrowck-capture-of-collaterally-moved",
+                                            "capture of collaterally moved value: `{path}`",
+                                            path = nl)
+                    }
+                    (MovedInCapture, _, _) => {
+                        diagnostic_message!("borrowck-capture-of-moved",
+                                            "capture of moved value: `{path}`",
+                                            path = nl)
+                    }
+                };
+                let err = struct_span_err_translated!(self.tcx.sess, use_span, E0382, msg);
                 (ol, moved_lp_msg, err)}
         };
 
         // Get type of value and span where it was previously
         // moved.
-        let (move_span, move_note) = match the_move.kind {
+        let (move_span, into_closure) = match the_move.kind {
             move_data::Declared => {
                 unreachable!();
             }
 
             move_data::MoveExpr |
             move_data::MovePat =>
-                (self.tcx.hir.span(the_move.id), ""),
+                (self.tcx.hir.span(the_move.id), false),
 
             move_data::Captured =>
                 (match self.tcx.hir.expect_expr(the_move.id).node {
                     hir::ExprClosure(.., fn_decl_span) => fn_decl_span,
                     ref r => bug!("Captured({}) maps to non-closure: {:?}",
                                   the_move.id, r),
-                }, " (into closure)"),
+                }, true),
         };
 
         // Annotate the use and the move in the span. Watch out for
         // the case where the use and the move are the same. This
         // means the use is in a loop.
+        let sess = self.tcx.sess;
         err = if use_span == move_span {
-            err.span_label(
-                use_span,
-                &format!("value moved{} here in previous iteration of loop",
-                         move_note));
+            let label = if into_closure {
+                diagnostic_message!("borrowck-moved-into-closure-in-previous-iteration",
+                                    "value moved (into closure) here in previous iteration \
+                                     of loop")
+            } else {
+                diagnostic_message!("borrowck-moved-in-previous-iteration",
+                                    "value moved here in previous iteration of loop")
+            };
+            err.span_label(use_span, &sess.translate(&label));
             err
         } else {
-            err.span_label(use_span, &format!("value {} here after move", verb_participle))
-               .span_label(move_span, &format!("value moved{} here", move_note));
+            let use_label = match use_kind {
+                MovedInUse => {
+                    diagnostic_message!("borrowck-used-after-move", "value used here after move")
+                }
+                MovedInCapture => {
+                    diagnostic_message!("borrowck-captured-after-move",
+                                        "value captured here after move")
+                }
+            };
+            let move_label = if into_closure {
+                diagnostic_message!("borrowck-moved-into-closure-here",
+                                    "value moved (into closure) here")
+            } else {
+                diagnostic_message!("borrowck-moved-here", "value moved here")
+            };
+            err.span_label(use_span, &sess.translate(&use_label))
+               .span_label(move_span, &sess.translate(&move_label));
             err
         };
 
-        err.note(&format!("move occurs because `{}` has type `{}`, \
-                           which does not implement the `Copy` trait",
-                          self.loan_path_to_string(moved_lp),
-                          moved_lp.ty));
+        err.note(&sess.translate(&diagnostic_message!(
+            "borrowck-move-of-non-copy",
+            "move occurs because `{path}` has type `{ty}`, \
+             which does not implement the `Copy` trait",
+            path = self.loan_path_to_string(moved_lp),
+            ty = moved_lp.ty.to_string())));
 
         // Note: we used to suggest adding a `ref binding` or calling
         // `clone` but those suggestions have been removed because
@@ -648,6 +726,21 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
         self.tcx.sess.struct_span_err(s, m)
     }
 
+    pub fn translate(&self, msg: &Message) -> String {
+        self.tcx.sess.translate(msg)
+    }
+
+    /// How a borrow of `kind` is described in messages, like "mutable".
+    pub fn borrow_kind_str(&self, kind: ty::BorrowKind) -> String {
+        self.translate(&match kind {
+            ty::MutBorrow => diagnostic_message!("borrowck-kind-mutable", "mutable"),
+            ty::ImmBorrow => diagnostic_message!("borrowck-kind-immutable", "immutable"),
+            ty::UniqueImmBorrow => {
+                diagnostic_message!("borrowck-kind-uniquely-immutable", "uniquely immutable")
+            }
+        })
+    }
+
     pub fn struct_span_err_with_code<S: Into<MultiSpan>>(&self,
                                                          s: S,
                                                          msg: &str,
@@ -937,10 +1030,10 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
     {
         let cmt_path_or_string = self.cmt_to_path_or_string(&err.cmt);
 
-        let suggestion =
+        let (suggestion, applicability) =
             match self.tcx.sess.codemap().span_to_snippet(err.span) {
-                Ok(string) => format!("move {}", string),
-                Err(_) => format!("move |<args>| <body>")
+                Ok(string) => (format!("move {}", string), Applicability::MachineApplicable),
+                Err(_) => (format!("move |<args>| <body>"), Applicability::HasPlaceholders),
             };
 
         struct_span_err!(self.tcx.sess, err.span, E0373,
@@ -954,12 +1047,14 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
             .span_label(err.span,
                        &format!("may outlive borrowed value {}",
                                 cmt_path_or_string))
-            .span_suggestion(err.span,
-                             &format!("to force the closure to take ownership of {} \
-                                       (and any other referenced variables), \
-                                       use the `move` keyword, as shown:",
-                                       cmt_path_or_string),
-                             suggestion)
+            .span_suggestion_with_applicability(
+                err.span,
+                &format!("to force the closure to take ownership of {} \
+                          (and any other referenced variables), \
+                          use the `move` keyword, as shown:",
+                         cmt_path_or_string),
+                suggestion,
+                applicability)
             .emit();
     }
 
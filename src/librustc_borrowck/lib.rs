@@ -24,6 +24,7 @@
 #![feature(rustc_private)]
 #![feature(staged_api)]
 #![feature(associated_consts)]
+#![feature(i128_type)]
 #![feature(nonzero)]
 #[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
@@ -154,6 +154,7 @@ pub fn compile_input(sess: &Session,
                                                                   &resolutions,
                                                                   &expanded_crate,
                                                                   &hir_map.krate(),
+                                                                  &outputs,
                                                                   &crate_name),
                                     Ok(()));
         }
@@ -431,10 +432,12 @@ impl<'a, 'tcx> CompileState<'a, 'tcx> {
                                 resolutions: &'a Resolutions,
                                 krate: &'a ast::Crate,
                                 hir_crate: &'a hir::Crate,
+                                output_filenames: &'a OutputFilenames,
                                 crate_name: &'a str)
                                 -> Self {
         CompileState {
             crate_name: Some(crate_name),
+            output_filenames: Some(output_filenames),
             arena: Some(arena),
             arenas: Some(arenas),
             cstore: Some(cstore),
@@ -1183,14 +1186,17 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, crate_name: &str) {
         (|| -> io::Result<()> {
             // Build a list of files used to compile the output and
             // write Makefile-compatible dependency rules
-            let files: Vec<String> = sess.codemap()
-                                         .files
-                                         .borrow()
-                                         .iter()
-                                         .filter(|fmap| fmap.is_real_file())
-                                         .filter(|fmap| !fmap.is_imported())
-                                         .map(|fmap| escape_dep_filename(&fmap.name))
-                                         .collect();
+            let mut files: Vec<String> = sess.codemap()
+                                             .files
+                                             .borrow()
+                                             .iter()
+                                             .filter(|fmap| fmap.is_real_file())
+                                             .filter(|fmap| !fmap.is_imported())
+                                             .map(|fmap| escape_dep_filename(&fmap.name))
+                                             .collect();
+            if let Some(ref config) = sess.opts.lint_config {
+                files.push(escape_dep_filename(&config.path.display().to_string()));
+            }
             let mut file = fs::File::create(&deps_filename)?;
             for path in &out_filenames {
                 write!(file, "{}: {}\n\n", path.display(), files.join(" "))?;
@@ -0,0 +1,504 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small interface for tools that embed the compiler to analyze a crate.
+//!
+//! `CompilerCalls` and `CompileController` give access to every phase of the
+//! compiler, in terms of its internal types, which change from one release
+//! to the next. This module covers what most analysis tools need with a
+//! handful of types that only expose strings, numbers and locations:
+//!
+//! ```ignore
+//! let mut compiler = Compiler::new("src/lib.rs");
+//! compiler.arg("--crate-type=lib")
+//!         .source("src/lib.rs", "pub fn answer() -> u32 { 42 }");
+//! let signatures = compiler.analyze(|krate| {
+//!     krate.items()
+//!          .into_iter()
+//!          .filter_map(|item| krate.signature(item.id).map(|sig| (item.path, sig)))
+//!          .collect::<Vec<_>>()
+//! })?;
+//! ```
+//!
+//! The crate is parsed, expanded and type-checked, and compilation stops
+//! there. Its diagnostics are written to standard error unless given
+//! somewhere else, and if there are errors the closure is not called.
+//!
+//! Like `run_compiler`, `analyze` needs a large stack; tools should call it
+//! from a thread made with `in_rustc_thread`. The standard library is looked
+//! for in the sysroot of the running executable, which tools that aren't
+//! installed next to rustc should give with `--sysroot`.
+
+use driver::{CompileController, CompileState};
+use {run_compiler, Compilation, CompilerCalls};
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc::hir::itemlikevisit::ItemLikeVisitor;
+use rustc::hir::map as hir_map;
+use rustc::session::Session;
+use rustc::ty::{TyCtxt, TypeckTables};
+use errors::FatalError;
+use getopts;
+use syntax::ast;
+use syntax::codemap::{FileLoader, RealFileLoader};
+use syntax_pos::Span;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub struct Compiler {
+    root: PathBuf,
+    args: Vec<String>,
+    sources: HashMap<PathBuf, String>,
+    diagnostics: Option<Box<Write + Send>>,
+}
+
+impl Compiler {
+    /// A compiler for the crate whose root module is in the file at `root`.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Compiler {
+        Compiler {
+            root: root.into(),
+            args: vec![],
+            sources: HashMap::new(),
+            diagnostics: None,
+        }
+    }
+
+    /// Pass `arg` to the compiler, as on the command line.
+    pub fn arg<S: Into<String>>(&mut self, arg: S) -> &mut Compiler {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Use `source` as the contents of the file at `path`, instead of reading
+    /// the file. This holds for modules and `include!`d files as well as the
+    /// crate root. Files not given this way are read from the file system.
+    pub fn source<P, S>(&mut self, path: P, source: S) -> &mut Compiler
+        where P: Into<PathBuf>, S: Into<String>
+    {
+        self.sources.insert(path.into(), source.into());
+        self
+    }
+
+    /// Write diagnostics to `dst` instead of standard error.
+    pub fn diagnostics(&mut self, dst: Box<Write + Send>) -> &mut Compiler {
+        self.diagnostics = Some(dst);
+        self
+    }
+
+    /// Type-check the crate, and unless that fails, call `f` with the result.
+    pub fn analyze<F, R>(&mut self, f: F) -> Result<R, CompileError>
+        where F: for<'a, 'tcx> FnOnce(&Crate<'a, 'tcx>) -> R
+    {
+        let mut args = vec!["rustc".to_string()];
+        args.extend(self.args.iter().cloned());
+        args.push(self.root.to_string_lossy().into_owned());
+
+        let loader: Box<FileLoader> = box MemoryFileLoader { sources: self.sources.clone() };
+        let diagnostics = self.diagnostics.take();
+        let f = RefCell::new(Some(f));
+        let result = RefCell::new(None);
+
+        // Fatal errors unwind, with their diagnostic already emitted. Anything
+        // else that panics is a bug, whose message is printed before it goes on.
+        let panic_output = Arc::new(Mutex::new(Vec::new()));
+        let old_panic = io::set_panic(Some(box Sink(panic_output.clone())));
+        let compiled = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut calls = AnalysisCalls { f: &f, result: &result };
+            run_compiler(&args, &mut calls, Some(loader), diagnostics)
+        }));
+        io::set_panic(old_panic);
+
+        let session = match compiled {
+            Ok((_, session)) => session,
+            Err(value) => {
+                if !value.is::<FatalError>() {
+                    let output = panic_output.lock().unwrap();
+                    let _ = io::stderr().write_all(&output);
+                    panic::resume_unwind(value);
+                }
+                None
+            }
+        };
+        match result.into_inner() {
+            Some(r) => Ok(r),
+            None => Err(CompileError { errors: session.map_or(0, |sess| sess.err_count()) }),
+        }
+    }
+}
+
+/// The crate could not be analyzed, because of the diagnostics it was given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileError {
+    /// How many errors there were. Fatal errors that stop compilation
+    /// straight away are not counted.
+    pub errors: usize,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.errors {
+            0 => write!(f, "compilation failed"),
+            1 => write!(f, "compilation failed with 1 error"),
+            n => write!(f, "compilation failed with {} errors", n),
+        }
+    }
+}
+
+/// A type-checked crate.
+pub struct Crate<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+}
+
+/// Identifies an item of the crate, for as long as it is being analyzed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ItemId(DefId);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Impl,
+    Const,
+    Static,
+    /// A type alias or associated type.
+    Type,
+    Module,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub id: ItemId,
+    /// The name of the item, which is empty for impls.
+    pub name: String,
+    /// The path of the item from the root of its crate, like
+    /// `net::Socket::connect`.
+    pub path: String,
+    pub kind: ItemKind,
+    pub location: Location,
+}
+
+/// Where something is in the source, with lines and columns counted from 1.
+/// The end is exclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// The type of a function or method, written as in Rust.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub inputs: Vec<String>,
+    pub output: String,
+}
+
+/// An expression, and its type written as in Rust.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedExpr {
+    pub location: Location,
+    pub ty: String,
+}
+
+impl<'a, 'tcx> Crate<'a, 'tcx> {
+    /// The name of the crate.
+    pub fn name(&self) -> String {
+        self.tcx.crate_name.to_string()
+    }
+
+    /// Every item of the crate, including those inside functions and the
+    /// items of traits and impls, in no particular order.
+    pub fn items(&self) -> Vec<Item> {
+        let mut collector = ItemCollector { krate: self, items: vec![] };
+        self.tcx.hir.krate().visit_all_item_likes(&mut collector);
+        collector.items
+    }
+
+    /// The type of `item`, written as in Rust, if it has one. Modules,
+    /// traits, imports and associated types without a default have none.
+    pub fn type_of(&self, item: ItemId) -> Option<String> {
+        if !self.has_type(item) {
+            return None;
+        }
+        Some(self.tcx.item_type(item.0).to_string())
+    }
+
+    /// The signature of `item`, if it is a function or method.
+    pub fn signature(&self, item: ItemId) -> Option<Signature> {
+        if !self.has_type(item) {
+            return None;
+        }
+        let ty = self.tcx.item_type(item.0);
+        if !ty.is_fn() {
+            return None;
+        }
+        let sig = ty.fn_sig();
+        let sig = sig.skip_binder();
+        Some(Signature {
+            inputs: sig.inputs().iter().map(|ty| ty.to_string()).collect(),
+            output: sig.output().to_string(),
+        })
+    }
+
+    /// The expressions in the body of `item`, with their types, in the order
+    /// they are in the source. Items without a body have none.
+    pub fn expressions(&self, item: ItemId) -> Vec<TypedExpr> {
+        let body_id = match self.body_of(item) {
+            Some(body_id) => body_id,
+            None => return vec![],
+        };
+        let mut collector = ExprCollector {
+            krate: self,
+            tables: self.tcx.item_tables(item.0),
+            exprs: vec![],
+        };
+        collector.visit_body(self.tcx.hir.body(body_id));
+        collector.exprs
+    }
+
+    fn has_type(&self, item: ItemId) -> bool {
+        let node_id = match self.tcx.hir.as_local_node_id(item.0) {
+            Some(node_id) => node_id,
+            None => return true,
+        };
+        match self.tcx.hir.get(node_id) {
+            hir_map::NodeItem(item) => match item.node {
+                hir::ItemFn(..) |
+                hir::ItemStatic(..) |
+                hir::ItemConst(..) |
+                hir::ItemTy(..) |
+                hir::ItemEnum(..) |
+                hir::ItemStruct(..) |
+                hir::ItemUnion(..) |
+                hir::ItemImpl(..) => true,
+                _ => false,
+            },
+            hir_map::NodeTraitItem(item) => match item.node {
+                hir::TraitItemKind::Type(_, None) => false,
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn body_of(&self, item: ItemId) -> Option<hir::BodyId> {
+        let node_id = match self.tcx.hir.as_local_node_id(item.0) {
+            Some(node_id) => node_id,
+            None => return None,
+        };
+        match self.tcx.hir.get(node_id) {
+            hir_map::NodeItem(item) => match item.node {
+                hir::ItemFn(.., body) |
+                hir::ItemConst(_, body) |
+                hir::ItemStatic(_, _, body) => Some(body),
+                _ => None,
+            },
+            hir_map::NodeTraitItem(item) => match item.node {
+                hir::TraitItemKind::Method(_, hir::TraitMethod::Provided(body)) |
+                hir::TraitItemKind::Const(_, Some(body)) => Some(body),
+                _ => None,
+            },
+            hir_map::NodeImplItem(item) => match item.node {
+                hir::ImplItemKind::Method(_, body) |
+                hir::ImplItemKind::Const(_, body) => Some(body),
+                hir::ImplItemKind::Type(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn location(&self, span: Span) -> Location {
+        let codemap = self.tcx.sess.codemap();
+        let lo = codemap.lookup_char_pos(span.lo);
+        let hi = codemap.lookup_char_pos(span.hi);
+        Location {
+            file: lo.file.name.clone(),
+            line: lo.line,
+            column: lo.col.0 + 1,
+            end_line: hi.line,
+            end_column: hi.col.0 + 1,
+        }
+    }
+
+    fn item(&self, id: ast::NodeId, name: ast::Name, kind: ItemKind, span: Span) -> Item {
+        let def_id = self.tcx.hir.local_def_id(id);
+        Item {
+            id: ItemId(def_id),
+            name: name.to_string(),
+            path: self.tcx.item_path_str(def_id),
+            kind: kind,
+            location: self.location(span),
+        }
+    }
+}
+
+struct ItemCollector<'k, 'a: 'k, 'tcx: 'a> {
+    krate: &'k Crate<'a, 'tcx>,
+    items: Vec<Item>,
+}
+
+impl<'k, 'a, 'tcx, 'hir> ItemLikeVisitor<'hir> for ItemCollector<'k, 'a, 'tcx> {
+    fn visit_item(&mut self, item: &'hir hir::Item) {
+        let kind = match item.node {
+            hir::ItemFn(..) => ItemKind::Function,
+            hir::ItemStruct(..) => ItemKind::Struct,
+            hir::ItemEnum(..) => ItemKind::Enum,
+            hir::ItemUnion(..) => ItemKind::Union,
+            hir::ItemTrait(..) => ItemKind::Trait,
+            hir::ItemImpl(..) => ItemKind::Impl,
+            hir::ItemConst(..) => ItemKind::Const,
+            hir::ItemStatic(..) => ItemKind::Static,
+            hir::ItemTy(..) => ItemKind::Type,
+            hir::ItemMod(..) => ItemKind::Module,
+            _ => ItemKind::Other,
+        };
+        let item = self.krate.item(item.id, item.name, kind, item.span);
+        self.items.push(item);
+    }
+
+    fn visit_trait_item(&mut self, item: &'hir hir::TraitItem) {
+        let kind = match item.node {
+            hir::TraitItemKind::Method(..) => ItemKind::Method,
+            hir::TraitItemKind::Const(..) => ItemKind::Const,
+            hir::TraitItemKind::Type(..) => ItemKind::Type,
+        };
+        let item = self.krate.item(item.id, item.name, kind, item.span);
+        self.items.push(item);
+    }
+
+    fn visit_impl_item(&mut self, item: &'hir hir::ImplItem) {
+        let kind = match item.node {
+            hir::ImplItemKind::Method(..) => ItemKind::Method,
+            hir::ImplItemKind::Const(..) => ItemKind::Const,
+            hir::ImplItemKind::Type(..) => ItemKind::Type,
+        };
+        let item = self.krate.item(item.id, item.name, kind, item.span);
+        self.items.push(item);
+    }
+}
+
+struct ExprCollector<'k, 'a: 'k, 'tcx: 'a> {
+    krate: &'k Crate<'a, 'tcx>,
+    tables: &'tcx TypeckTables<'tcx>,
+    exprs: Vec<TypedExpr>,
+}
+
+impl<'k, 'a, 'tcx> Visitor<'tcx> for ExprCollector<'k, 'a, 'tcx> {
+    // Closures are part of the body they are in, but their bodies are nested.
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(&self.krate.tcx.hir)
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let Some(ty) = self.tables.expr_ty_opt(expr) {
+            self.exprs.push(TypedExpr {
+                location: self.krate.location(expr.span),
+                ty: ty.to_string(),
+            });
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+struct AnalysisCalls<'a, F: 'a, R: 'a> {
+    f: &'a RefCell<Option<F>>,
+    result: &'a RefCell<Option<R>>,
+}
+
+impl<'a, F, R> CompilerCalls<'a> for AnalysisCalls<'a, F, R>
+    where F: for<'b, 'tcx> FnOnce(&Crate<'b, 'tcx>) -> R
+{
+    fn build_controller(&mut self, _: &Session, _: &getopts::Matches) -> CompileController<'a> {
+        let (f, result) = (self.f, self.result);
+        let mut control = CompileController::basic();
+        control.after_analysis.stop = Compilation::Stop;
+        control.after_analysis.callback = box move |state: &mut CompileState| {
+            if state.session.has_errors() {
+                return;
+            }
+            if let (Some(tcx), Some(f)) = (state.tcx, f.borrow_mut().take()) {
+                *result.borrow_mut() = Some(f(&Crate { tcx: tcx }));
+            }
+        };
+        control
+    }
+}
+
+/// Gives the sources the files were given, and reads the others.
+struct MemoryFileLoader {
+    sources: HashMap<PathBuf, String>,
+}
+
+impl FileLoader for MemoryFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.sources.contains_key(path) || RealFileLoader.file_exists(path)
+    }
+
+    fn abs_path(&self, path: &Path) -> Option<PathBuf> {
+        RealFileLoader.abs_path(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.sources.get(path) {
+            Some(source) => Ok(source.clone()),
+            None => RealFileLoader.read_file(path),
+        }
+    }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.sources.get(path) {
+            Some(source) => Ok(source.clone().into_bytes()),
+            None => RealFileLoader.read_binary_file(path),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.sources.contains_key(path) {
+            Ok(self.abs_path(path).unwrap_or(path.to_path_buf()))
+        } else {
+            RealFileLoader.canonicalize(path)
+        }
+    }
+
+    fn real_path(&self, path: &Path) -> Option<PathBuf> {
+        if self.sources.contains_key(path) {
+            None
+        } else {
+            RealFileLoader.real_path(path)
+        }
+    }
+}
+
+struct Sink(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Write::write(&mut *self.0.lock().unwrap(), data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
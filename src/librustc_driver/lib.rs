@@ -71,13 +71,18 @@ use rustc_save_analysis::DumpHandler;
 use rustc_trans::back::link;
 use rustc_trans::back::write::{create_target_machine, RELOC_MODEL_ARGS, CODE_GEN_MODEL_ARGS};
 use rustc::dep_graph::DepGraph;
+use rustc::hir;
 use rustc::session::{self, config, Session, build_session, CompileResult};
 use rustc::session::config::{Input, PrintRequest, OutputType, ErrorOutputType};
+use rustc::session::config::OutputFilenames;
 use rustc::session::config::nightly_options;
 use rustc::session::{early_error, early_warn};
 use rustc::lint::Lint;
 use rustc::lint;
+use rustc::middle::cstore::NativeLibraryKind;
+use rustc::middle::dependency_format;
 use rustc_metadata::locator;
+use rustc_metadata::unused_pub;
 use rustc_metadata::cstore::CStore;
 use rustc::util::common::time;
 
@@ -98,6 +103,7 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 
 use syntax::ast;
+use syntax::attr;
 use syntax::codemap::{CodeMap, FileLoader, RealFileLoader};
 use syntax::feature_gate::{GatedCfg, UnstableFeatures};
 use syntax::parse::{self, PResult};
@@ -107,9 +113,11 @@ use syntax_pos::{DUMMY_SP, MultiSpan};
 pub mod test;
 
 pub mod driver;
+pub mod embed;
 pub mod pretty;
 pub mod target_features;
 mod derive_registrar;
+mod suggestions;
 
 const BUG_REPORT_URL: &'static str = "https://github.com/rust-lang/rust/blob/master/CONTRIBUTING.\
                                       md#bug-reports";
@@ -140,7 +148,10 @@ pub fn run<F>(run_compiler: F) -> isize
         if let Err(err_count) = result {
             if err_count > 0 {
                 match session {
-                    Some(sess) => sess.fatal(&abort_msg(err_count)),
+                    Some(sess) => {
+                        sess.diagnostic().note_suppressed_errors();
+                        sess.fatal(&abort_msg(err_count))
+                    }
                     None => {
                         let emitter =
                             errors::emitter::EmitterWriter::stderr(errors::ColorConfig::Auto, None);
@@ -220,8 +231,12 @@ pub fn run_compiler<'a>(args: &[String],
 
     let plugins = sess.opts.debugging_opts.extra_plugins.clone();
     let control = callbacks.build_controller(&sess, &matches);
-    (driver::compile_input(&sess, &cstore, &input, &odir, &ofile, Some(plugins), &control),
-     Some(sess))
+    let result = driver::compile_input(&sess, &cstore, &input, &odir, &ofile, Some(plugins),
+                                       &control);
+    if sess.opts.debugging_opts.apply_suggestions {
+        suggestions::apply_suggestions(&sess);
+    }
+    (result, Some(sess))
 }
 
 // Extract output directory and file from matches.
@@ -418,6 +433,14 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 let mut cfg = config::build_configuration(&sess, cfg.clone());
                 target_features::add_configuration(&mut cfg, &sess);
                 sess.parse_sess.config = cfg;
+                if !sess.opts.debugging_opts.unused_pub.is_empty() {
+                    let stdout = io::stdout();
+                    unused_pub::print_unused_pub(&sess,
+                                                 &sess.opts.debugging_opts.unused_pub,
+                                                 &mut stdout.lock()).unwrap();
+                    sess.abort_if_errors();
+                    return None;
+                }
                 let should_stop =
                     RustcDefaultCalls::print_crate_info(&sess, None, odir, ofile);
 
@@ -496,6 +519,16 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             control.after_hir_lowering.stop = Compilation::Stop;
         }
 
+        if sess.opts.prints.iter().any(|req| req.needs_expansion()) {
+            control.after_hir_lowering.stop = Compilation::Stop;
+            control.after_hir_lowering.callback = box |state| {
+                RustcDefaultCalls::print_expanded_crate_info(state.session,
+                                                             state.hir_crate.unwrap(),
+                                                             state.output_filenames.unwrap(),
+                                                             state.crate_name.unwrap());
+            };
+        }
+
         if !sess.opts.output_types.keys().any(|&i| i == OutputType::Exe ||
                                                    i == OutputType::Metadata) {
             control.after_llvm.stop = Compilation::Stop;
@@ -557,7 +590,10 @@ impl RustcDefaultCalls {
                 &Input::File(ref ifile) => {
                     let path = &(*ifile);
                     let mut v = Vec::new();
-                    locator::list_file_metadata(&sess.target.target, path, &mut v).unwrap();
+                    locator::list_file_metadata(&sess.target.target,
+                                                sess.codemap().file_loader(),
+                                                path,
+                                                &mut v).unwrap();
                     println!("{}", String::from_utf8(v).unwrap());
                 }
                 &Input::Str { .. } => {
@@ -673,9 +709,61 @@ impl RustcDefaultCalls {
                     }
                     println!("");
                 }
+                PrintRequest::SourceFiles |
+                PrintRequest::NativeLibs |
+                PrintRequest::LinkArgs => {
+                    // Printed by `print_expanded_crate_info` once the crate
+                    // has been expanded.
+                    if input.is_none() {
+                        early_error(ErrorOutputType::default(), "no input file provided");
+                    }
+                }
+            }
+        }
+
+        if sess.opts.prints.iter().any(|req| req.needs_expansion()) {
+            Compilation::Continue
+        } else {
+            Compilation::Stop
+        }
+    }
+
+    fn print_expanded_crate_info(sess: &Session,
+                                 krate: &hir::Crate,
+                                 outputs: &OutputFilenames,
+                                 crate_name: &str) {
+        for req in sess.opts.prints.iter().filter(|req| req.needs_expansion()) {
+            match *req {
+                PrintRequest::SourceFiles => {
+                    // Everything loaded into the codemap from disk after cfg
+                    // evaluation, i.e. the modules and the `include!`d files.
+                    for fmap in sess.codemap().files.borrow().iter() {
+                        if fmap.is_real_file() && !fmap.is_imported() {
+                            println!("{}", fmap.name);
+                        }
+                    }
+                }
+                PrintRequest::NativeLibs => {
+                    for lib in link::native_libraries(sess) {
+                        let kind = match lib.kind {
+                            NativeLibraryKind::NativeStatic => "static",
+                            NativeLibraryKind::NativeStaticNobundle => "static-nobundle",
+                            NativeLibraryKind::NativeFramework => "framework",
+                            NativeLibraryKind::NativeUnknown => "dylib",
+                        };
+                        println!("{}={}", kind, lib.name);
+                    }
+                }
+                PrintRequest::LinkArgs => {
+                    dependency_format::calculate(sess);
+                    let windows_subsystem =
+                        attr::first_attr_value_str_by_name(&krate.attrs, "windows_subsystem")
+                            .map(|subsystem| subsystem.to_string());
+                    link::print_link_args(sess, outputs, crate_name, windows_subsystem);
+                }
+                _ => {}
             }
         }
-        return Compilation::Stop;
     }
 }
 
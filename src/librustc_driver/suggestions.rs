@@ -0,0 +1,116 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rewriting the source files with the machine-applicable suggestions made
+//! during compilation (`-Z apply-suggestions`).
+
+use rustc::session::Session;
+use syntax_pos::{FileMap, NO_EXPANSION};
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::rc::Rc;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Edit {
+    lo: usize,
+    hi: usize,
+    text: String,
+}
+
+pub fn apply_suggestions(sess: &Session) {
+    let cm = sess.codemap();
+    let mut files: BTreeMap<String, (Rc<FileMap>, Vec<Edit>)> = BTreeMap::new();
+
+    'suggestions: for suggestion in sess.diagnostic().take_suggestions() {
+        let mut edits = vec![];
+        for (span, substitute) in suggestion.msp.primary_spans().iter()
+                                            .zip(&suggestion.substitutes) {
+            // Code produced by a macro can't be fixed where it appears.
+            if span.ctxt != NO_EXPANSION {
+                continue 'suggestions;
+            }
+            let lo = cm.lookup_byte_offset(span.lo);
+            let hi = cm.lookup_byte_offset(span.hi);
+            if !lo.fm.is_real_file() || lo.fm.is_imported() || lo.fm.name != hi.fm.name {
+                continue 'suggestions;
+            }
+            edits.push((lo.fm, Edit {
+                lo: lo.pos.0 as usize,
+                hi: hi.pos.0 as usize,
+                text: substitute.clone(),
+            }));
+        }
+        for (fm, edit) in edits {
+            files.entry(fm.name.clone()).or_insert_with(|| (fm, vec![])).1.push(edit);
+        }
+    }
+
+    for (_, (fm, mut edits)) in files {
+        let src = match fm.src {
+            Some(ref src) => src,
+            None => continue,
+        };
+        edits.sort();
+        // The same suggestion is made once per instantiation of generic code.
+        edits.dedup();
+
+        let mut fixed = String::with_capacity(src.len());
+        let (mut applied, mut skipped) = (0, 0);
+        let mut pos = 0;
+        for edit in edits {
+            if edit.lo < pos {
+                skipped += 1;
+                continue;
+            }
+            let (lo, hi) = if edit.text.is_empty() {
+                whole_line(src, edit.lo, edit.hi)
+            } else {
+                (edit.lo, edit.hi)
+            };
+            fixed.push_str(&src[pos..lo]);
+            fixed.push_str(&edit.text);
+            pos = hi;
+            applied += 1;
+        }
+        fixed.push_str(&src[pos..]);
+
+        let path = fm.abs_path.as_ref().unwrap_or(&fm.name);
+        match File::create(path).and_then(|mut f| f.write_all(fixed.as_bytes())) {
+            Ok(()) => {
+                sess.note_without_error(&format!("applied {} suggestion{} to `{}`",
+                                                 applied,
+                                                 if applied == 1 { "" } else { "s" },
+                                                 fm.name));
+            }
+            Err(e) => sess.err(&format!("failed to write `{}`: {}", path, e)),
+        }
+        if skipped > 0 {
+            sess.warn(&format!("skipped {} overlapping suggestion{} in `{}`, \
+                                compile again to apply them",
+                               skipped,
+                               if skipped == 1 { "" } else { "s" },
+                               fm.name));
+        }
+    }
+}
+
+/// When removing `lo..hi` would leave nothing but whitespace on its line,
+/// widens it to remove the whole line instead.
+fn whole_line(src: &str, lo: usize, hi: usize) -> (usize, usize) {
+    let line_start = src[..lo].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = src[hi..].find('\n').map_or(src.len(), |i| hi + i + 1);
+    if src[line_start..lo].trim().is_empty() && src[hi..line_end].trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (lo, hi)
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Translations of diagnostic messages.
+//!
+//! A message that can be translated is built as a `Message`: an ID, its text
+//! in English, and the values of the `{name}` placeholders in that text. A
+//! `Catalog` read from a file gives the text for each ID in another language:
+//!
+//! ```text
+//! # Messages in French.
+//! typeck-mismatched-types = "types incompatibles"
+//! typeck-expected-found = "{expected} attendu, {found} trouvé"
+//! ```
+//!
+//! Messages missing from the catalog, and those whose translation uses a
+//! placeholder the message has no value for, are given in English.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The value of a placeholder in a message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiagnosticArg {
+    Str(String),
+    Number(u64),
+}
+
+impl fmt::Display for DiagnosticArg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DiagnosticArg::Str(ref s) => s.fmt(f),
+            DiagnosticArg::Number(n) => n.fmt(f),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DiagnosticArg {
+    fn from(s: &'a str) -> DiagnosticArg {
+        DiagnosticArg::Str(s.to_owned())
+    }
+}
+
+impl From<String> for DiagnosticArg {
+    fn from(s: String) -> DiagnosticArg {
+        DiagnosticArg::Str(s)
+    }
+}
+
+impl From<usize> for DiagnosticArg {
+    fn from(n: usize) -> DiagnosticArg {
+        DiagnosticArg::Number(n as u64)
+    }
+}
+
+impl From<u64> for DiagnosticArg {
+    fn from(n: u64) -> DiagnosticArg {
+        DiagnosticArg::Number(n)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Message {
+    /// The ID of the message in catalogs.
+    pub id: &'static str,
+    /// The text of the message in English.
+    pub fallback: &'static str,
+    pub args: Vec<(&'static str, DiagnosticArg)>,
+}
+
+impl Message {
+    pub fn new(id: &'static str, fallback: &'static str) -> Message {
+        Message {
+            id: id,
+            fallback: fallback,
+            args: vec![],
+        }
+    }
+
+    /// Give `value` to the `{name}` placeholders of the message.
+    pub fn arg<T: Into<DiagnosticArg>>(mut self, name: &'static str, value: T) -> Message {
+        self.args.push((name, value.into()));
+        self
+    }
+
+    /// The message in English.
+    pub fn to_english(&self) -> String {
+        match self.format(self.fallback) {
+            Some(s) => s,
+            None => panic!("no value for a placeholder of message `{}`", self.id),
+        }
+    }
+
+    /// Fill in the placeholders of `template`, unless it has one the message
+    /// has no value for. `{{` and `}}` stand for braces.
+    fn format(&self, template: &str) -> Option<String> {
+        let mut s = String::with_capacity(template.len());
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' | '}' if chars.as_str().starts_with(c) => {
+                    chars.next();
+                    s.push(c);
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = match rest.find('}') {
+                        Some(end) => end,
+                        None => return None,
+                    };
+                    let name = rest[..end].trim();
+                    match self.args.iter().find(|&&(arg, _)| arg == name) {
+                        Some(&(_, ref value)) => s.push_str(&value.to_string()),
+                        None => return None,
+                    }
+                    chars = rest[end + 1..].chars();
+                }
+                c => s.push(c),
+            }
+        }
+        Some(s)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Catalog {
+    /// The file the catalog was read from.
+    pub path: PathBuf,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn read(path: &Path) -> Result<Catalog, String> {
+        let mut src = String::new();
+        File::open(path).and_then(|mut f| f.read_to_string(&mut src)).map_err(|e| {
+            format!("couldn't read diagnostic catalog `{}`: {}", path.display(), e)
+        })?;
+        Catalog::parse(path, &src)
+    }
+
+    pub fn parse(path: &Path, src: &str) -> Result<Catalog, String> {
+        let mut messages = HashMap::new();
+        for (i, line) in src.lines().enumerate() {
+            let error = |msg: &str| format!("{}:{}: {}", path.display(), i + 1, msg);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let id = parts.next().unwrap().trim();
+            let value = match parts.next() {
+                Some(value) => value.trim(),
+                None => return Err(error("expected `message-id = \"text\"`")),
+            };
+            if id.is_empty() ||
+               !id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+                return Err(error(&format!("invalid message ID `{}`", id)));
+            }
+            let text = match unquote(value) {
+                Some(text) => text,
+                None => return Err(error("expected the text of the message in double quotes")),
+            };
+            if messages.insert(id.to_owned(), text).is_some() {
+                return Err(error(&format!("message `{}` is given more than once", id)));
+            }
+        }
+        Ok(Catalog {
+            path: path.to_path_buf(),
+            messages: messages,
+        })
+    }
+
+    /// The message in the language of the catalog, or in English if the
+    /// catalog has no usable translation of it.
+    pub fn translate(&self, msg: &Message) -> String {
+        self.messages
+            .get(msg.id)
+            .and_then(|text| msg.format(text))
+            .unwrap_or_else(|| msg.to_english())
+    }
+}
+
+/// Parses a double-quoted string with `\"`, `\\`, `\n` and `\t` escapes,
+/// which may be followed by a comment.
+fn unquote(s: &str) -> Option<String> {
+    let mut chars = s.chars();
+    if chars.next() != Some('"') {
+        return None;
+    }
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => break,
+            Some('\\') => {
+                match chars.next() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    _ => return None,
+                }
+            }
+            Some(c) => text.push(c),
+            None => return None,
+        }
+    }
+    let rest = chars.as_str().trim();
+    if rest.is_empty() || rest.starts_with('#') {
+        Some(text)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Catalog, Message};
+    use std::path::Path;
+
+    fn mismatch() -> Message {
+        Message::new("typeck-expected-found", "expected {expected}, found {found}")
+            .arg("expected", "u32")
+            .arg("found", "bool")
+    }
+
+    #[test]
+    fn translate() {
+        let catalog = Catalog::parse(Path::new("fr.catalog"), r#"
+# Messages in French.
+typeck-expected-found = "{expected} attendu, {found} trouvé"  # comment
+typeck-mismatched-types = "types \"incompatibles\" {{{count}}}"
+"#).unwrap();
+        assert_eq!(catalog.translate(&mismatch()), "u32 attendu, bool trouvé");
+        let msg = Message::new("typeck-mismatched-types", "mismatched types").arg("count", 2usize);
+        assert_eq!(catalog.translate(&msg), "types \"incompatibles\" {2}");
+        let missing = Message::new("borrowck-use-moved", "use of moved value");
+        assert_eq!(catalog.translate(&missing), "use of moved value");
+    }
+
+    #[test]
+    fn fall_back_on_unknown_placeholder() {
+        let catalog = Catalog::parse(Path::new("fr.catalog"),
+                                     "typeck-expected-found = \"{attendu} attendu\"").unwrap();
+        assert_eq!(catalog.translate(&mismatch()), "expected u32, found bool");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |src| Catalog::parse(Path::new("fr.catalog"), src).unwrap_err();
+        assert_eq!(parse("\nfoo"), "fr.catalog:2: expected `message-id = \"text\"`");
+        assert!(parse("foo = bar").starts_with("fr.catalog:1: expected the text"));
+        assert!(parse("a = \"1\"\na = \"2\"").starts_with("fr.catalog:2: message `a`"));
+    }
+}
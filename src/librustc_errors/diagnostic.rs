@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use Applicability;
 use CodeSuggestion;
 use Level;
 use RenderSpan;
@@ -208,12 +209,24 @@ impl Diagnostic {
                                                msg: &str,
                                                suggestion: String)
                                                -> &mut Self {
+        self.span_suggestion_with_applicability(sp, msg, suggestion, Applicability::Unspecified)
+    }
+
+    /// Like `span_suggestion`, also telling how sure we are that the
+    /// suggestion is right, which decides whether tools may apply it.
+    pub fn span_suggestion_with_applicability<S: Into<MultiSpan>>(&mut self,
+                                                                  sp: S,
+                                                                  msg: &str,
+                                                                  suggestion: String,
+                                                                  applicability: Applicability)
+                                                                  -> &mut Self {
         self.sub(Level::Help,
                  msg,
                  MultiSpan::new(),
                  Some(Suggestion(CodeSuggestion {
                      msp: sp.into(),
                      substitutes: vec![suggestion],
+                     applicability: applicability,
                  })));
         self
     }
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use Applicability;
 use Diagnostic;
 use DiagnosticStyledString;
 
@@ -97,7 +98,13 @@ impl<'a> DiagnosticBuilder<'a> {
             }
         }
 
+        if !self.handler.should_emit(&self.diagnostic) {
+            self.cancel();
+            return;
+        }
+
         self.handler.emitter.borrow_mut().emit(&self);
+        self.handler.record_suggestions(&self.diagnostic);
         self.cancel();
         self.handler.panic_if_treat_err_as_bug();
 
@@ -146,6 +153,13 @@ impl<'a> DiagnosticBuilder<'a> {
                                                         msg: &str,
                                                         suggestion: String)
                                                         -> &mut Self);
+    forward!(pub fn span_suggestion_with_applicability<S: Into<MultiSpan>>(
+                                                        &mut self,
+                                                        sp: S,
+                                                        msg: &str,
+                                                        suggestion: String,
+                                                        applicability: Applicability)
+                                                        -> &mut Self);
     forward!(pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self);
     forward!(pub fn code(&mut self, s: String) -> &mut Self);
 
@@ -35,6 +35,14 @@ impl Emitter for EmitterWriter {
         let mut primary_span = db.span.clone();
         let mut children = db.children.clone();
         self.fix_multispans_in_std_macros(&mut primary_span, &mut children);
+        if self.short_message {
+            self.emit_messages_short(&db.level,
+                                     &db.styled_message(),
+                                     &db.code,
+                                     &primary_span,
+                                     &children);
+            return;
+        }
         self.emit_messages_default(&db.level,
                                    &db.styled_message(),
                                    &db.code,
@@ -66,6 +74,11 @@ impl ColorConfig {
 pub struct EmitterWriter {
     dst: Destination,
     cm: Option<Rc<CodeMapper>>,
+    /// Whether to print each diagnostic as `file:line:col: level: message`
+    /// lines, without the source snippets.
+    short_message: bool,
+    /// The width source lines are truncated to, if any.
+    terminal_width: Option<usize>,
 }
 
 struct FileWithAnnotatedLines {
@@ -96,11 +109,15 @@ impl EmitterWriter {
             EmitterWriter {
                 dst: dst,
                 cm: code_map,
+                short_message: false,
+                terminal_width: None,
             }
         } else {
             EmitterWriter {
                 dst: Raw(Box::new(io::stderr())),
                 cm: code_map,
+                short_message: false,
+                terminal_width: None,
             }
         }
     }
@@ -109,9 +126,25 @@ impl EmitterWriter {
         EmitterWriter {
             dst: Raw(dst),
             cm: code_map,
+            short_message: false,
+            terminal_width: None,
         }
     }
 
+    /// Print one line per message, `file:line:col: level[code]: message`,
+    /// instead of the source snippets.
+    pub fn short_message(mut self, short_message: bool) -> EmitterWriter {
+        self.short_message = short_message;
+        self
+    }
+
+    /// Truncate source lines longer than `width` columns around the part of
+    /// them that is annotated, instead of letting the terminal wrap them.
+    pub fn terminal_width(mut self, width: Option<usize>) -> EmitterWriter {
+        self.terminal_width = width;
+        self
+    }
+
     fn preprocess_annotations(&self, msp: &MultiSpan) -> Vec<FileWithAnnotatedLines> {
         fn add_annotation_to_file(file_vec: &mut Vec<FileWithAnnotatedLines>,
                                   file: Rc<FileMap>,
@@ -944,6 +977,14 @@ impl EmitterWriter {
                                                      width_offset,
                                                      code_offset);
 
+                if let Some(width) = self.terminal_width {
+                    truncate_source_line(&mut buffer,
+                                         &annotated_file.lines[line_idx],
+                                         previous_buffer_line,
+                                         code_offset,
+                                         width);
+                }
+
                 let mut to_add = HashMap::new();
 
                 for (depth, style) in depths {
@@ -1019,6 +1060,81 @@ impl EmitterWriter {
 
         Ok(())
     }
+
+    /// The location of the primary span of `msp` as `file:line:col`, if it
+    /// has one.
+    fn short_location(&self, msp: &MultiSpan) -> Option<String> {
+        match (self.cm.as_ref(), msp.primary_span()) {
+            (Some(cm), Some(span)) if span != DUMMY_SP => {
+                let loc = cm.lookup_char_pos(span.lo);
+                Some(format!("{}:{}:{}", loc.file.name, loc.line, loc.col.0 + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Add a `file:line:col: level[code]: message` line to `buffer`, with the
+    /// message folded onto that one line.
+    fn short_message_to_buffer(&self,
+                               buffer: &mut StyledBuffer,
+                               location: Option<&String>,
+                               level: &Level,
+                               code: &Option<String>,
+                               msg: &Vec<(String, Style)>) {
+        let line = buffer.num_lines();
+        if let Some(location) = location {
+            buffer.append(line, location, Style::LineAndColumn);
+            buffer.append(line, ": ", Style::NoStyle);
+        }
+        buffer.append(line, &level.to_string(), Style::Level(level.clone()));
+        if let &Some(ref code) = code {
+            buffer.append(line, &format!("[{}]", code), Style::Level(level.clone()));
+        }
+        buffer.append(line, ": ", Style::HeaderMsg);
+        let text = msg.iter().map(|&(ref text, _)| &text[..]).collect::<String>();
+        let text = text.split('\n').map(|l| l.trim()).collect::<Vec<_>>().join(" ");
+        buffer.append(line, text.trim(), Style::HeaderMsg);
+    }
+
+    fn emit_messages_short(&mut self,
+                           level: &Level,
+                           message: &Vec<(String, Style)>,
+                           code: &Option<String>,
+                           span: &MultiSpan,
+                           children: &Vec<SubDiagnostic>) {
+        let mut buffer = StyledBuffer::new();
+        let primary_location = self.short_location(span);
+        self.short_message_to_buffer(&mut buffer, primary_location.as_ref(), level, code, message);
+
+        // Children without a span of their own are about the primary one, so
+        // they are given its location for tools that jump to it.
+        for child in children {
+            let mut message = child.styled_message().clone();
+            let location = match child.render_span {
+                Some(FullSpan(ref msp)) => self.short_location(msp),
+                Some(Suggestion(ref cs)) => {
+                    if cs.substitutes.iter().any(|s| !s.is_empty()) {
+                        message.push((format!(": `{}`", cs.substitutes.join("`, `")),
+                                      Style::NoStyle));
+                    }
+                    self.short_location(&cs.msp)
+                }
+                None => self.short_location(&child.span),
+            };
+            let location = location.or_else(|| primary_location.clone());
+            self.short_message_to_buffer(&mut buffer,
+                                         location.as_ref(),
+                                         &child.level,
+                                         &None,
+                                         &message);
+        }
+
+        match emit_to_destination(&buffer.render(), level, &mut self.dst) {
+            Ok(()) => (),
+            Err(e) => panic!("failed to emit error: {}", e),
+        }
+    }
+
     fn emit_suggestion_default(&mut self,
                                suggestion: &CodeSuggestion,
                                level: &Level,
@@ -1181,6 +1297,41 @@ fn overlaps(a1: &Annotation, a2: &Annotation, padding: usize) -> bool {
     num_overlap(a1.start_col, a1.end_col + padding, a2.start_col, a2.end_col, false)
 }
 
+/// Cut the source line rendered at `row` down to `width` columns when it is
+/// longer, keeping the annotated part of it in view. When the start of the
+/// line has to go, the annotations below it are moved along with the code.
+fn truncate_source_line(buffer: &mut StyledBuffer,
+                        line: &Line,
+                        row: usize,
+                        code_offset: usize,
+                        width: usize) {
+    const ELLIPSIS: &'static str = "...";
+
+    if buffer.line_len(row) <= width || code_offset + 4 * ELLIPSIS.len() > width {
+        return;
+    }
+    let available = width - code_offset;
+    let span_lo = line.annotations.iter().map(|a| a.start_col).min().unwrap_or(0);
+    let span_hi = line.annotations.iter().map(|a| a.end_col).max().unwrap_or(0);
+
+    // Keep the start of the line if the annotations fit without cutting it,
+    // otherwise leave a quarter of the room for the code before them.
+    let left = if span_hi + ELLIPSIS.len() <= available {
+        0
+    } else {
+        span_lo.saturating_sub(available / 4)
+    };
+    if left > ELLIPSIS.len() {
+        let lines = buffer.num_lines();
+        buffer.remove_columns(row..lines, code_offset, left - ELLIPSIS.len());
+        buffer.puts(row, code_offset, ELLIPSIS, Style::LineNumber);
+    }
+    if buffer.line_len(row) > width {
+        buffer.truncate(row, width - ELLIPSIS.len());
+        buffer.append(row, ELLIPSIS, Style::LineNumber);
+    }
+}
+
 fn emit_to_destination(rendered_buffer: &Vec<Vec<StyledString>>,
                        lvl: &Level,
                        dst: &mut Destination)
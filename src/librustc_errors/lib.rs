@@ -33,12 +33,16 @@ pub use emitter::ColorConfig;
 
 use self::Level::*;
 
+use catalog::{Catalog, Message};
 use emitter::{Emitter, EmitterWriter};
 
 use std::cell::{RefCell, Cell};
+use std::collections::HashSet;
+use std::mem;
 use std::{error, fmt};
 use std::rc::Rc;
 
+pub mod catalog;
 pub mod diagnostic;
 pub mod diagnostic_builder;
 pub mod emitter;
@@ -67,6 +71,27 @@ pub enum RenderSpan {
 pub struct CodeSuggestion {
     pub msp: MultiSpan,
     pub substitutes: Vec<String>,
+    pub applicability: Applicability,
+}
+
+/// How confident the compiler is that a suggestion is correct, and thus
+/// whether tools may apply it without a human looking at it.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be
+    /// applied automatically.
+    MachineApplicable,
+
+    /// The suggestion contains placeholders like `<args>` which have to be
+    /// filled in by the user.
+    HasPlaceholders,
+
+    /// The suggestion may be what the user intended, but that is uncertain;
+    /// it should compile, but may change what the code means.
+    MaybeIncorrect,
+
+    /// Nothing is known about the suggestion.
+    Unspecified,
 }
 
 pub trait CodeMapper {
@@ -216,6 +241,22 @@ pub struct Handler {
     treat_err_as_bug: bool,
     continue_after_error: Cell<bool>,
     delayed_span_bug: RefCell<Option<(MultiSpan, String)>>,
+    // The machine-applicable suggestions emitted so far, if they are being
+    // collected at all.
+    suggestions: RefCell<Option<Vec<CodeSuggestion>>>,
+
+    // How many errors to emit at most; the rest are only counted.
+    error_limit: Cell<Option<usize>>,
+    emitted_errors: Cell<usize>,
+    suppressed_errors: Cell<usize>,
+
+    // Whether to emit each diagnostic only once, and the code, primary spans
+    // and message of those emitted so far.
+    deduplicate: Cell<bool>,
+    emitted_diagnostics: RefCell<HashSet<(Option<String>, Vec<Span>, String)>>,
+
+    // The translations of messages, if they aren't to be given in English.
+    catalog: RefCell<Option<Catalog>>,
 }
 
 impl Handler {
@@ -239,6 +280,13 @@ impl Handler {
             treat_err_as_bug: treat_err_as_bug,
             continue_after_error: Cell::new(true),
             delayed_span_bug: RefCell::new(None),
+            suggestions: RefCell::new(None),
+            error_limit: Cell::new(None),
+            emitted_errors: Cell::new(0),
+            suppressed_errors: Cell::new(0),
+            deduplicate: Cell::new(false),
+            emitted_diagnostics: RefCell::new(HashSet::new()),
+            catalog: RefCell::new(None),
         }
     }
 
@@ -246,6 +294,106 @@ impl Handler {
         self.continue_after_error.set(continue_after_error);
     }
 
+    /// Emit no more than `limit` errors. Errors past the limit still count
+    /// towards `err_count`, they just aren't shown.
+    pub fn set_error_limit(&self, limit: Option<usize>) {
+        self.error_limit.set(limit);
+    }
+
+    /// Drop diagnostics with the same code, primary spans and message as one
+    /// already emitted, as cascading errors often come in many copies. This
+    /// is off unless asked for, as tests count the copies.
+    pub fn set_deduplicate(&self, deduplicate: bool) {
+        self.deduplicate.set(deduplicate);
+    }
+
+    /// Translate messages with `catalog`, or give them in English if `None`.
+    pub fn set_catalog(&self, catalog: Option<Catalog>) {
+        *self.catalog.borrow_mut() = catalog;
+    }
+
+    /// The text of `msg` in the language of the catalog, if there is one.
+    pub fn translate(&self, msg: &Message) -> String {
+        match *self.catalog.borrow() {
+            Some(ref catalog) => catalog.translate(msg),
+            None => msg.to_english(),
+        }
+    }
+
+    /// Notes how many errors went unreported because of the error limit.
+    pub fn note_suppressed_errors(&self) {
+        let suppressed = self.suppressed_errors.get();
+        self.suppressed_errors.set(0);
+        let msg = match suppressed {
+            0 => return,
+            1 => Message::new("errors-suppressed-error",
+                              "1 more error suppressed by `-Z error-limit`"),
+            _ => Message::new("errors-suppressed-errors",
+                              "{count} more errors suppressed by `-Z error-limit`"),
+        };
+        self.note_without_error(&self.translate(&msg.arg("count", suppressed)));
+    }
+
+    // Decides whether `diagnostic` is to be shown, keeping the books for the
+    // error limit and deduplication.
+    fn should_emit(&self, diagnostic: &Diagnostic) -> bool {
+        // With `-Z treat-err-as-bug` the diagnostic is about to panic, and is
+        // shown for that whether or not it would have been suppressed.
+        if self.treat_err_as_bug {
+            return true;
+        }
+        if self.deduplicate.get() {
+            let key = (diagnostic.code.clone(),
+                       diagnostic.span.primary_spans().to_vec(),
+                       diagnostic.message());
+            if !self.emitted_diagnostics.borrow_mut().insert(key) {
+                return false;
+            }
+        }
+        match diagnostic.level {
+            Error | PhaseFatal => {
+                match self.error_limit.get() {
+                    Some(limit) if self.emitted_errors.get() >= limit => {
+                        self.suppressed_errors.set(self.suppressed_errors.get() + 1);
+                        return false;
+                    }
+                    _ => self.emitted_errors.set(self.emitted_errors.get() + 1),
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Keep the machine-applicable suggestions of all diagnostics emitted
+    /// from now on, for `take_suggestions` to return.
+    pub fn collect_suggestions(&self) {
+        let mut suggestions = self.suggestions.borrow_mut();
+        if suggestions.is_none() {
+            *suggestions = Some(vec![]);
+        }
+    }
+
+    /// Returns the machine-applicable suggestions collected so far.
+    pub fn take_suggestions(&self) -> Vec<CodeSuggestion> {
+        match *self.suggestions.borrow_mut() {
+            Some(ref mut suggestions) => mem::replace(suggestions, vec![]),
+            None => vec![],
+        }
+    }
+
+    fn record_suggestions(&self, diagnostic: &Diagnostic) {
+        if let Some(ref mut suggestions) = *self.suggestions.borrow_mut() {
+            for child in &diagnostic.children {
+                if let Some(RenderSpan::Suggestion(ref suggestion)) = child.render_span {
+                    if suggestion.applicability == Applicability::MachineApplicable {
+                        suggestions.push(suggestion.clone());
+                    }
+                }
+            }
+        }
+    }
+
     pub fn struct_dummy<'a>(&'a self) -> DiagnosticBuilder<'a> {
         DiagnosticBuilder::new(self, Level::Cancelled, "")
     }
@@ -444,11 +592,17 @@ impl Handler {
 
                 return;
             }
-            1 => s = "aborting due to previous error".to_string(),
-            _ => {
-                s = format!("aborting due to {} previous errors", self.err_count.get());
+            1 => {
+                s = self.translate(&Message::new("errors-aborting-error",
+                                                 "aborting due to previous error"));
+            }
+            count => {
+                s = self.translate(&Message::new("errors-aborting-errors",
+                                                 "aborting due to {count} previous errors")
+                                       .arg("count", count));
             }
         }
+        self.note_suppressed_errors();
 
         panic!(self.fatal(&s));
     }
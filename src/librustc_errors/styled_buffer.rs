@@ -12,6 +12,9 @@
 
 use snippet::{Style, StyledString};
 
+use std::cmp::min;
+use std::ops::Range;
+
 #[derive(Debug)]
 pub struct StyledBuffer {
     text: Vec<Vec<char>>,
@@ -139,6 +142,29 @@ impl StyledBuffer {
         }
     }
 
+    /// Remove `count` columns from each of `lines`, starting at `col`, and
+    /// shift what follows them to the left.
+    pub fn remove_columns(&mut self, lines: Range<usize>, col: usize, count: usize) {
+        for line in lines {
+            if line < self.text.len() && col < self.text[line].len() {
+                let end = min(col + count, self.text[line].len());
+                self.text[line].drain(col..end);
+                self.styles[line].drain(col..end);
+            }
+        }
+    }
+
+    pub fn truncate(&mut self, line: usize, len: usize) {
+        if line < self.text.len() {
+            self.text[line].truncate(len);
+            self.styles[line].truncate(len);
+        }
+    }
+
+    pub fn line_len(&self, line: usize) -> usize {
+        self.text.get(line).map_or(0, |text| text.len())
+    }
+
     pub fn num_lines(&self) -> usize {
         self.text.len()
     }
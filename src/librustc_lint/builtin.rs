@@ -39,12 +39,13 @@ use util::nodemap::NodeSet;
 use lint::{Level, LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass, EarlyLintPass, EarlyContext};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use syntax::ast;
 use syntax::attr;
 use syntax::feature_gate::{AttributeGate, AttributeType, Stability, deprecated_attributes};
-use syntax_pos::Span;
+use syntax::parse::lexer::comments::{Comment, gather_comments_and_literals};
+use syntax_pos::{BytePos, Span, NO_EXPANSION};
 
 use rustc::hir::{self, PatKind};
 use rustc::hir::intravisit::FnKind;
@@ -185,12 +186,78 @@ declare_lint! {
     "usage of `unsafe` code"
 }
 
-#[derive(Copy, Clone)]
-pub struct UnsafeCode;
+declare_lint! {
+    UNDOCUMENTED_UNSAFE,
+    Allow,
+    "`unsafe` blocks without a `// SAFETY:` comment"
+}
+
+pub struct UnsafeCode {
+    /// The comments of the files with `unsafe` blocks, by the start of the file.
+    comments: HashMap<BytePos, Vec<Comment>>,
+}
+
+impl UnsafeCode {
+    pub fn new() -> UnsafeCode {
+        UnsafeCode { comments: HashMap::new() }
+    }
+}
 
 impl LintPass for UnsafeCode {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNSAFE_CODE)
+        lint_array!(UNSAFE_CODE, UNDOCUMENTED_UNSAFE)
+    }
+}
+
+impl UnsafeCode {
+    /// Whether a `SAFETY:` comment is on the line `span` starts on, or in
+    /// the comment lines just above it.
+    fn has_safety_comment(&mut self, cx: &LateContext, span: Span) -> bool {
+        // Whether the text of a comment, once its markers are taken off,
+        // starts with `SAFETY:`.
+        fn is_safety_comment(comment: &str) -> bool {
+            comment.trim_left_matches(|c: char| c == '/' || c == '*' || c == '!')
+                   .trim_left()
+                   .starts_with("SAFETY:")
+        }
+
+        let loc = cx.sess().codemap().lookup_char_pos(span.lo);
+        let file = loc.file;
+        let src = match file.src {
+            Some(ref src) => src.clone(),
+            None => return false,
+        };
+        let mut line = loc.line - 1;
+
+        // The comments on the line are found by lexing the file, as a `//`
+        // may as well be in a string literal.
+        let comments = self.comments.entry(file.start_pos).or_insert_with(|| {
+            gather_comments_and_literals(&cx.sess().parse_sess,
+                                         file.name.clone(),
+                                         &mut src.as_bytes()).0
+        });
+        let on_the_line = comments.iter().any(|comment| {
+            file.lookup_line(file.start_pos + comment.pos) == Some(line) &&
+            comment.lines.first().map_or(false, |text| is_safety_comment(text))
+        });
+        if on_the_line {
+            return true;
+        }
+
+        while line > 0 {
+            line -= 1;
+            let text = match file.get_line(line) {
+                Some(text) => text.trim_left(),
+                None => return false,
+            };
+            if !text.starts_with("//") && !text.starts_with("/*") && !text.starts_with("*") {
+                return false;
+            }
+            if is_safety_comment(text) {
+                return true;
+            }
+        }
+        false
     }
 }
 
@@ -200,6 +267,16 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnsafeCode {
             // Don't warn about generated blocks, that'll just pollute the output.
             if blk.rules == hir::UnsafeBlock(hir::UserProvided) {
                 cx.span_lint(UNSAFE_CODE, blk.span, "usage of an `unsafe` block");
+
+                // The comments of blocks from macros are in the macro, if
+                // anywhere, so these are left alone.
+                if blk.span.ctxt == NO_EXPANSION &&
+                   !self.has_safety_comment(cx, blk.span) {
+                    cx.span_lint(UNDOCUMENTED_UNSAFE,
+                                 blk.span,
+                                 "`unsafe` block without a `// SAFETY:` comment explaining \
+                                  why it is sound");
+                }
             }
         }
     }
@@ -132,7 +132,6 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                  NonUpperCaseGlobals,
                  NonShorthandFieldPatterns,
                  UnusedUnsafe,
-                 UnsafeCode,
                  UnusedMut,
                  UnusedAllocation,
                  MissingCopyImplementations,
@@ -148,6 +147,7 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                           TypeLimits,
                           MissingDoc,
                           MissingDebugImplementations,
+                          UnsafeCode,
                           );
 
     add_lint_group!(sess,
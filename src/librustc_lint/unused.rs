@@ -10,7 +10,7 @@
 
 use rustc::ty;
 use rustc::ty::adjustment;
-use util::nodemap::FxHashMap;
+use util::nodemap::{FxHashMap, FxHashSet};
 use lint::{LateContext, EarlyContext, LintContext, LintArray};
 use lint::{LintPass, EarlyLintPass, LateLintPass};
 
@@ -18,6 +18,7 @@ use std::collections::hash_map::Entry::{Occupied, Vacant};
 
 use syntax::ast;
 use syntax::attr;
+use syntax::errors::Applicability;
 use syntax::feature_gate::{BUILTIN_ATTRIBUTES, AttributeType};
 use syntax::symbol::keywords;
 use syntax::ptr::P;
@@ -25,6 +26,8 @@ use syntax_pos::Span;
 
 use rustc_back::slice;
 use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
 use rustc::hir::intravisit::FnKind;
 
 declare_lint! {
@@ -63,9 +66,26 @@ impl UnusedMut {
         let used_mutables = cx.tcx.used_mut_nodes.borrow();
         for (_, v) in &mutables {
             if !v.iter().any(|e| used_mutables.contains(e)) {
-                cx.span_lint(UNUSED_MUT,
-                             cx.tcx.hir.span(v[0]),
-                             "variable does not need to be mutable");
+                let span = cx.tcx.hir.span(v[0]);
+                let mut err = cx.struct_span_lint(UNUSED_MUT,
+                                                  span,
+                                                  "variable does not need to be mutable");
+                if let Ok(snippet) = cx.sess().codemap().span_to_snippet(span) {
+                    if snippet.starts_with("mut") {
+                        // With several patterns binding the variable, only
+                        // fixing the first one makes them inconsistent.
+                        let applicability = if v.len() == 1 {
+                            Applicability::MachineApplicable
+                        } else {
+                            Applicability::MaybeIncorrect
+                        };
+                        err.span_suggestion_with_applicability(span,
+                                                               "remove this `mut`",
+                                                               snippet[3..].trim_left().to_owned(),
+                                                               applicability);
+                    }
+                }
+                err.emit();
             }
         }
     }
@@ -110,7 +130,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedMut {
 declare_lint! {
     pub UNUSED_MUST_USE,
     Warn,
-    "unused result of a type flagged as #[must_use]"
+    "unused result of a type or function flagged as #[must_use]"
 }
 
 declare_lint! {
@@ -140,24 +160,63 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedResults {
         }
 
         let t = cx.tables.expr_ty(&expr);
-        let warned = match t.sty {
+        let ty_warned = match t.sty {
             ty::TyTuple(ref tys, _) if tys.is_empty() => return,
             ty::TyNever => return,
-            ty::TyBool => return,
             ty::TyAdt(def, _) => {
                 let attrs = cx.tcx.get_attrs(def.did);
-                check_must_use(cx, &attrs, s.span)
+                check_must_use(cx, &attrs, s.span, "unused result which must be used")
+            }
+            _ => false,
+        };
+
+        // A `#[must_use]` function or method makes its result must-use
+        // whatever its type, with `#![feature(fn_must_use)]`.
+        let fn_must_use = cx.tcx.sess.features.borrow().fn_must_use;
+        let fn_warned = !ty_warned && fn_must_use && match callee_def_id(cx, expr) {
+            Some(def_id) => {
+                let attrs = cx.tcx.get_attrs(def_id);
+                let msg = format!("unused return value of `{}` which must be used",
+                                  cx.tcx.item_path_str(def_id));
+                check_must_use(cx, &attrs, s.span, &msg)
             }
+            None => false,
+        };
+
+        let is_bool = match t.sty {
+            ty::TyBool => true,
             _ => false,
         };
-        if !warned {
+        if !ty_warned && !fn_warned && !is_bool {
             cx.span_lint(UNUSED_RESULTS, s.span, "unused result");
         }
 
-        fn check_must_use(cx: &LateContext, attrs: &[ast::Attribute], sp: Span) -> bool {
+        fn callee_def_id(cx: &LateContext, expr: &hir::Expr) -> Option<DefId> {
+            match expr.node {
+                hir::ExprCall(ref callee, _) => {
+                    match callee.node {
+                        hir::ExprPath(ref qpath) => {
+                            match cx.tables.qpath_def(qpath, callee.id) {
+                                Def::Fn(def_id) | Def::Method(def_id) => Some(def_id),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+                hir::ExprMethodCall(..) => {
+                    let method_call = ty::MethodCall::expr(expr.id);
+                    cx.tables.method_map.get(&method_call).map(|method| method.def_id)
+                }
+                _ => None,
+            }
+        }
+
+        fn check_must_use(cx: &LateContext, attrs: &[ast::Attribute], sp: Span, msg: &str)
+                          -> bool {
             for attr in attrs {
                 if attr.check_name("must_use") {
-                    let mut msg = "unused result which must be used".to_string();
+                    let mut msg = msg.to_string();
                     // check for #[must_use="..."]
                     if let Some(s) = attr.value_str() {
                         msg.push_str(": ");
@@ -178,12 +237,65 @@ declare_lint! {
     "unnecessary use of an `unsafe` block"
 }
 
+declare_lint! {
+    pub PARTIALLY_UNUSED_UNSAFE,
+    Allow,
+    "`unsafe` blocks with statements that don't need `unsafe`"
+}
+
 #[derive(Copy, Clone)]
 pub struct UnusedUnsafe;
 
 impl LintPass for UnusedUnsafe {
     fn get_lints(&self) -> LintArray {
-        lint_array!(UNUSED_UNSAFE)
+        lint_array!(UNUSED_UNSAFE, PARTIALLY_UNUSED_UNSAFE)
+    }
+}
+
+impl UnusedUnsafe {
+    /// The statements of a used `unsafe` block, and its trailing expression,
+    /// that don't hold any operation needing `unsafe`. A trailing expression
+    /// that only names a value, like a local the block computed, is left out:
+    /// it can't be moved out of the block on its own.
+    fn unneeded_parts(cx: &LateContext, blk: &hir::Block) -> Vec<Span> {
+        fn names_value(expr: &hir::Expr) -> bool {
+            match expr.node {
+                hir::ExprPath(_) | hir::ExprLit(_) => true,
+                hir::ExprField(ref base, _) | hir::ExprTupField(ref base, _) => names_value(base),
+                _ => false,
+            }
+        }
+
+        let unsafe_ops = cx.tcx.unsafe_ops.borrow();
+        let ops = match unsafe_ops.get(&blk.id) {
+            Some(ops) => ops,
+            None => return vec![],
+        };
+
+        // The statement, or trailing expression, of the block each operation
+        // is in.
+        let mut needed = FxHashSet();
+        for &op in ops {
+            let mut id = op;
+            loop {
+                let parent = cx.tcx.hir.get_parent_node(id);
+                if parent == blk.id || parent == id {
+                    break;
+                }
+                id = parent;
+            }
+            needed.insert(id);
+        }
+
+        let stmts = blk.stmts
+            .iter()
+            .filter(|stmt| !needed.contains(&stmt.node.id()))
+            .map(|stmt| stmt.span);
+        let expr = blk.expr
+            .iter()
+            .filter(|expr| !needed.contains(&expr.id) && !names_value(expr))
+            .map(|expr| expr.span);
+        stmts.chain(expr).collect()
     }
 }
 
@@ -222,6 +334,20 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnusedUnsafe {
                 }
                 db.emit();
             }
+
+            if blk.rules == hir::UnsafeBlock(hir::UserProvided) {
+                let unneeded = UnusedUnsafe::unneeded_parts(cx, blk);
+                if !unneeded.is_empty() {
+                    let mut db = cx.struct_span_lint(PARTIALLY_UNUSED_UNSAFE, blk.span,
+                                                     "`unsafe` block holds code that doesn't \
+                                                      need `unsafe`");
+                    for span in unneeded {
+                        db.span_label(span, &"this doesn't need `unsafe`");
+                    }
+                    db.help("move it out of the `unsafe` block");
+                    db.emit();
+                }
+            }
         }
     }
 }
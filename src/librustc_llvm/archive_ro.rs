@@ -11,6 +11,7 @@
 //! A wrapper around LLVM's archive (.a) code
 
 use ArchiveRef;
+use libc::size_t;
 
 use std::ffi::CString;
 use std::marker;
@@ -63,6 +64,20 @@ impl ArchiveRO {
         }
     }
 
+    /// Reads a static archive from memory. The bytes are copied, so they
+    /// need not outlive the archive.
+    pub fn from_bytes(data: &[u8]) -> Option<ArchiveRO> {
+        unsafe {
+            let ar = ::LLVMRustOpenArchiveFromBuffer(data.as_ptr() as *const _,
+                                                     data.len() as size_t);
+            if ar.is_null() {
+                None
+            } else {
+                Some(ArchiveRO { ptr: ar })
+            }
+        }
+    }
+
     pub fn raw(&self) -> ArchiveRef {
         self.ptr
     }
@@ -30,9 +30,9 @@ use rustc::hir::map::Definitions;
 
 use std::cell::{RefCell, Cell};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::{cmp, fs};
+use std::cmp;
 
 use syntax::ast;
 use syntax::abi::Abi;
@@ -219,7 +219,7 @@ impl<'a> CrateLoader<'a> {
             let source = self.cstore.used_crate_source(cnum);
             if let Some(locs) = self.sess.opts.externs.get(&*name.as_str()) {
                 let found = locs.iter().any(|l| {
-                    let l = fs::canonicalize(l).ok();
+                    let l = self.sess.codemap().file_loader().canonicalize(Path::new(l)).ok();
                     source.dylib.as_ref().map(|p| &p.0) == l.as_ref() ||
                     source.rlib.as_ref().map(|p| &p.0) == l.as_ref()
                 });
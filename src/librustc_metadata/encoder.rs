@@ -16,8 +16,10 @@ use rustc::middle::cstore::{LinkMeta, LinkagePreference, NativeLibrary,
                             EncodedMetadata, EncodedMetadataHash};
 use rustc::hir::def_id::{CrateNum, CRATE_DEF_INDEX, DefIndex, DefId, LOCAL_CRATE};
 use rustc::hir::map::definitions::DefPathTable;
+use rustc::middle::dead;
 use rustc::middle::dependency_format::Linkage;
 use rustc::middle::lang_items;
+use rustc::middle::privacy::AccessLevels;
 use rustc::mir;
 use rustc::traits::specialization_graph;
 use rustc::ty::{self, Ty, TyCtxt, ReprOptions};
@@ -1308,6 +1310,43 @@ impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for ImplVisitor<'a, 'tcx> {
     }
 }
 
+struct ExportedItemsVisitor<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    access_levels: &'a AccessLevels,
+    exported: Vec<(DefIndex, String)>,
+}
+
+impl<'a, 'tcx> ExportedItemsVisitor<'a, 'tcx> {
+    fn insert(&mut self, id: ast::NodeId) {
+        if self.access_levels.is_exported(id) {
+            let def_id = self.tcx.hir.local_def_id(id);
+            self.exported.push((def_id.index, self.tcx.absolute_item_path_str(def_id)));
+        }
+    }
+}
+
+impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for ExportedItemsVisitor<'a, 'tcx> {
+    fn visit_item(&mut self, item: &hir::Item) {
+        match item.node {
+            hir::ItemFn(..) | hir::ItemStruct(..) | hir::ItemEnum(..) |
+            hir::ItemUnion(..) | hir::ItemTrait(..) | hir::ItemTy(..) |
+            hir::ItemConst(..) | hir::ItemStatic(..) => self.insert(item.id),
+            hir::ItemImpl(.., None, _, ref impl_item_refs) => {
+                for impl_item_ref in impl_item_refs {
+                    self.insert(impl_item_ref.id.node_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_trait_item(&mut self, _trait_item: &'v hir::TraitItem) {}
+
+    fn visit_impl_item(&mut self, _impl_item: &'v hir::ImplItem) {
+        // handled in `visit_item` above
+    }
+}
+
 impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
     /// Encodes an index, mapping each trait to its (local) implementations.
     fn encode_impls(&mut self) -> LazySeq<TraitImpls> {
@@ -1342,6 +1381,37 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
         self.lazy_seq(exported_symbols.iter().map(|&id| tcx.hir.local_def_id(id).index))
     }
 
+    /// Encodes the `pub` items of the crate and the items it uses, for
+    /// `-Z unused-pub` to find the ones no crate uses.
+    fn encode_item_uses(&mut self) -> Option<Lazy<ItemUses>> {
+        let tcx = self.tcx;
+        if !tcx.sess.opts.debugging_opts.record_item_uses {
+            return None;
+        }
+
+        let access_levels = ty::queries::privacy_access_levels::get(tcx, DUMMY_SP, LOCAL_CRATE);
+        let mut visitor = ExportedItemsVisitor {
+            tcx: tcx,
+            access_levels: &access_levels,
+            exported: vec![],
+        };
+        tcx.hir.krate().visit_all_item_likes(&mut visitor);
+        let mut exported = visitor.exported;
+        exported.sort();
+
+        let mut used: Vec<_> = dead::used_items(tcx)
+            .into_iter()
+            .map(|def_id| (def_id.krate.as_u32(), def_id.index))
+            .collect();
+        used.sort();
+
+        let item_uses = ItemUses {
+            exported: self.lazy_seq(exported),
+            used: self.lazy_seq(used),
+        };
+        Some(self.lazy(&item_uses))
+    }
+
     fn encode_dylib_dependency_formats(&mut self) -> LazySeq<Option<LinkagePreference>> {
         match self.tcx.sess.dependency_formats.borrow().get(&config::CrateTypeDylib) {
             Some(arr) => {
@@ -1397,6 +1467,11 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
         let exported_symbols = self.encode_exported_symbols();
         let exported_symbols_bytes = self.position() - i;
 
+        // Encode the uses of items, for `-Z unused-pub`.
+        i = self.position();
+        let item_uses = self.encode_item_uses();
+        let item_uses_bytes = self.position() - i;
+
         // Encode and index the items.
         i = self.position();
         let items = self.encode_info_for_items();
@@ -1435,6 +1510,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             def_path_table: def_path_table,
             impls: impls,
             exported_symbols: exported_symbols,
+            item_uses: item_uses,
             index: index,
         });
 
@@ -1455,6 +1531,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             println!("         codemap bytes: {}", codemap_bytes);
             println!("            impl bytes: {}", impl_bytes);
             println!("    exp. symbols bytes: {}", exported_symbols_bytes);
+            println!("       item uses bytes: {}", item_uses_bytes);
             println!("  def-path table bytes: {}", def_path_table_bytes);
             println!("            item bytes: {}", item_bytes);
             println!("           index bytes: {}", index_bytes);
@@ -62,5 +62,6 @@ mod schema;
 pub mod creader;
 pub mod cstore;
 pub mod locator;
+pub mod unused_pub;
 
 __build_diagnostic_array! { librustc_metadata, DIAGNOSTICS }
@@ -234,14 +234,14 @@ use rustc_llvm as llvm;
 use rustc_llvm::{False, ObjectFile, mk_section_iter};
 use rustc_llvm::archive_ro::ArchiveRO;
 use errors::DiagnosticBuilder;
+use syntax::codemap::FileLoader;
 use syntax::symbol::Symbol;
 use syntax_pos::Span;
 use rustc_back::target::Target;
 
 use std::cmp;
 use std::fmt;
-use std::fs::{self, File};
-use std::io::{self, Read};
+use std::io;
 use std::path::{Path, PathBuf};
 use std::ptr;
 use std::slice;
@@ -499,7 +499,7 @@ impl<'a> Context<'a> {
             let slot = candidates.entry(hash_str)
                 .or_insert_with(|| (FxHashMap(), FxHashMap(), FxHashMap()));
             let (ref mut rlibs, ref mut rmetas, ref mut dylibs) = *slot;
-            fs::canonicalize(path)
+            self.sess.codemap().file_loader().canonicalize(path)
                 .map(|p| {
                     match found_kind {
                         CrateFlavor::Rlib => { rlibs.insert(p, kind); }
@@ -596,7 +596,8 @@ impl<'a> Context<'a> {
         let mut err: Option<DiagnosticBuilder> = None;
         for (lib, kind) in m {
             info!("{} reading metadata from: {}", flavor, lib.display());
-            let (hash, metadata) = match get_metadata_section(self.target, flavor, &lib) {
+            let loader = self.sess.codemap().file_loader();
+            let (hash, metadata) = match get_metadata_section(self.target, loader, flavor, &lib) {
                 Ok(blob) => {
                     if let Some(h) = self.crate_matches(&blob, &lib) {
                         (h, blob)
@@ -661,7 +662,7 @@ impl<'a> Context<'a> {
             // as well.
             if let Some((ref prev, _)) = ret {
                 let sysroot = self.sess.sysroot();
-                let sysroot = sysroot.canonicalize()
+                let sysroot = self.sess.codemap().file_loader().canonicalize(sysroot)
                                      .unwrap_or(sysroot.to_path_buf());
                 if prev.starts_with(&sysroot) {
                     continue
@@ -754,13 +755,14 @@ impl<'a> Context<'a> {
         // files which actually exist that have the correct naming scheme for
         // rlibs/dylibs.
         let sess = self.sess;
+        let loader = sess.codemap().file_loader();
         let dylibname = self.dylibname();
         let mut rlibs = FxHashMap();
         let mut rmetas = FxHashMap();
         let mut dylibs = FxHashMap();
         {
             let locs = locs.map(|l| PathBuf::from(l)).filter(|loc| {
-                if !loc.exists() {
+                if !loader.file_exists(loc) {
                     sess.err(&format!("extern location for {} does not exist: {}",
                                       self.crate_name,
                                       loc.display()));
@@ -797,11 +799,11 @@ impl<'a> Context<'a> {
             // there's at most one rlib and at most one dylib.
             for loc in locs {
                 if loc.file_name().unwrap().to_str().unwrap().ends_with(".rlib") {
-                    rlibs.insert(fs::canonicalize(&loc).unwrap(), PathKind::ExternFlag);
+                    rlibs.insert(loader.canonicalize(&loc).unwrap(), PathKind::ExternFlag);
                 } else if loc.file_name().unwrap().to_str().unwrap().ends_with(".rmeta") {
-                    rmetas.insert(fs::canonicalize(&loc).unwrap(), PathKind::ExternFlag);
+                    rmetas.insert(loader.canonicalize(&loc).unwrap(), PathKind::ExternFlag);
                 } else {
-                    dylibs.insert(fs::canonicalize(&loc).unwrap(), PathKind::ExternFlag);
+                    dylibs.insert(loader.canonicalize(&loc).unwrap(), PathKind::ExternFlag);
                 }
             }
         };
@@ -872,11 +874,12 @@ fn verify_decompressed_encoding_version(blob: &MetadataBlob,
 
 // Just a small wrapper to time how long reading metadata takes.
 fn get_metadata_section(target: &Target,
+                        loader: &FileLoader,
                         flavor: CrateFlavor,
                         filename: &Path)
                         -> Result<MetadataBlob, String> {
     let start = Instant::now();
-    let ret = get_metadata_section_imp(target, flavor, filename);
+    let ret = get_metadata_section_imp(target, loader, flavor, filename);
     info!("reading {:?} => {:?}",
           filename.file_name().unwrap(),
           start.elapsed());
@@ -884,17 +887,27 @@ fn get_metadata_section(target: &Target,
 }
 
 fn get_metadata_section_imp(target: &Target,
+                            loader: &FileLoader,
                             flavor: CrateFlavor,
                             filename: &Path)
                             -> Result<MetadataBlob, String> {
-    if !filename.exists() {
+    if !loader.file_exists(filename) {
         return Err(format!("no such file: '{}'", filename.display()));
     }
+    // Files the loader has on disk are read by LLVM, which maps them into
+    // memory; the others are copied into LLVM from what the loader reads.
+    let real_path = loader.real_path(filename);
     if flavor == CrateFlavor::Rlib {
         // Use ArchiveRO for speed here, it's backed by LLVM and uses mmap
         // internally to read the file. We also avoid even using a memcpy by
         // just keeping the archive along while the metadata is in use.
-        let archive = match ArchiveRO::open(filename) {
+        let archive = match real_path {
+            Some(ref path) => ArchiveRO::open(path),
+            None => loader.read_binary_file(filename).ok().and_then(|data| {
+                ArchiveRO::from_bytes(&data)
+            }),
+        };
+        let archive = match archive {
             Some(ar) => ar,
             None => {
                 debug!("llvm didn't like `{}`", filename.display());
@@ -909,18 +922,29 @@ fn get_metadata_section_imp(target: &Target,
             }
         };
     } else if flavor == CrateFlavor::Rmeta {
-        let mut file = File::open(filename).map_err(|_|
-            format!("could not open file: '{}'", filename.display()))?;
-        let mut buf = vec![];
-        file.read_to_end(&mut buf).map_err(|_|
+        let buf = loader.read_binary_file(filename).map_err(|_|
             format!("failed to read rlib metadata: '{}'", filename.display()))?;
         let blob = MetadataBlob::Raw(buf);
         verify_decompressed_encoding_version(&blob, filename)?;
         return Ok(blob);
     }
+    let mb = match real_path {
+        Some(path) => unsafe {
+            let path = common::path2cstr(&path);
+            llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(path.as_ptr())
+        },
+        None => {
+            let data = loader.read_binary_file(filename).map_err(|_|
+                format!("error reading library: '{}'", filename.display()))?;
+            let name = common::path2cstr(filename);
+            unsafe {
+                llvm::LLVMCreateMemoryBufferWithMemoryRangeCopy(data.as_ptr() as *const _,
+                                                                data.len() as _,
+                                                                name.as_ptr())
+            }
+        }
+    };
     unsafe {
-        let buf = common::path2cstr(filename);
-        let mb = llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(buf.as_ptr());
         if mb as isize == 0 {
             return Err(format!("error reading library: '{}'", filename.display()));
         }
@@ -995,8 +1019,11 @@ pub fn read_meta_section_name(_target: &Target) -> &'static str {
     ".rustc"
 }
 
-// A diagnostic function for dumping crate metadata to an output stream
-pub fn list_file_metadata(target: &Target, path: &Path, out: &mut io::Write) -> io::Result<()> {
+// Reads the metadata of a crate file, telling its flavor from its extension
+pub fn read_file_metadata(target: &Target,
+                          loader: &FileLoader,
+                          path: &Path)
+                          -> Result<MetadataBlob, String> {
     let filename = path.file_name().unwrap().to_str().unwrap();
     let flavor = if filename.ends_with(".rlib") {
         CrateFlavor::Rlib
@@ -1005,7 +1032,16 @@ pub fn list_file_metadata(target: &Target, path: &Path, out: &mut io::Write) ->
     } else {
         CrateFlavor::Dylib
     };
-    match get_metadata_section(target, flavor, path) {
+    get_metadata_section(target, loader, flavor, path)
+}
+
+// A diagnostic function for dumping crate metadata to an output stream
+pub fn list_file_metadata(target: &Target,
+                          loader: &FileLoader,
+                          path: &Path,
+                          out: &mut io::Write)
+                          -> io::Result<()> {
+    match read_file_metadata(target, loader, path) {
         Ok(metadata) => metadata.list_crate_metadata(out),
         Err(msg) => write!(out, "{}\n", msg),
     }
@@ -205,6 +205,7 @@ pub struct CrateRoot {
     pub def_path_table: Lazy<hir::map::definitions::DefPathTable>,
     pub impls: LazySeq<TraitImpls>,
     pub exported_symbols: LazySeq<DefIndex>,
+    pub item_uses: Option<Lazy<ItemUses>>,
     pub index: LazySeq<index::Index>,
 }
 
@@ -221,6 +222,19 @@ pub struct TraitImpls {
     pub impls: LazySeq<DefIndex>,
 }
 
+/// The `pub` items of a crate and the items it uses, recorded under
+/// `-Z record-item-uses`. The `pub` items come with their paths, and a used
+/// item is given as the number of its crate, 0 for the crate itself and `n`
+/// for `crate_deps[n - 1]`, and its index.
+///
+/// Trait impls and the items in them aren't recorded: which ones are used is
+/// only known after monomorphization.
+#[derive(RustcEncodable, RustcDecodable)]
+pub struct ItemUses {
+    pub exported: LazySeq<(DefIndex, String)>,
+    pub used: LazySeq<(u32, DefIndex)>,
+}
+
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct Entry<'tcx> {
     pub kind: EntryKind<'tcx>,
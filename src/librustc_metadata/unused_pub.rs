@@ -0,0 +1,146 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Finds the `pub` items of a set of crates that none of them uses, for
+//! `-Z unused-pub`.
+//!
+//! Each crate must have been built with `-Z record-item-uses`, which records
+//! in its metadata its `pub` items and the items it uses, from itself or from
+//! its dependencies. An item is used if one of the crates uses it, or uses an
+//! item defined in it: using a variant or a constructor uses its enum or its
+//! struct, and using a method uses the impl it's in.
+
+use cstore::MetadataBlob;
+use locator;
+use schema::rustc_version;
+
+use rustc::hir::def_id::DefIndex;
+use rustc::hir::map::definitions::DefPathTable;
+use rustc::hir::svh::Svh;
+use rustc::session::Session;
+use rustc::util::nodemap::FxHashSet;
+
+use std::io;
+use std::path::Path;
+
+struct CrateUses {
+    hash: Svh,
+    deps: Vec<Svh>,
+    def_path_table: DefPathTable,
+    exported: Vec<(DefIndex, String)>,
+    used: Vec<(u32, DefIndex)>,
+}
+
+/// Prints the paths of the `pub` items that no crate uses, one per line. Each
+/// of `paths` is a crate file, or a directory whose crate files are read.
+pub fn print_unused_pub(sess: &Session, paths: &[String], out: &mut io::Write)
+                        -> io::Result<()> {
+    let mut crates: Vec<CrateUses> = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        match sess.codemap().file_loader().read_dir(path) {
+            Ok(mut files) => {
+                files.sort();
+                for file in files.into_iter().filter(|f| is_crate_file(sess, f)) {
+                    read_crate_uses(sess, &file, false, &mut crates);
+                }
+            }
+            Err(_) => read_crate_uses(sess, path, true, &mut crates),
+        }
+    }
+
+    // The uses of the items of crates that aren't in the set are dropped.
+    let mut used = FxHashSet();
+    for krate in &crates {
+        for &(cnum, index) in &krate.used {
+            let hash = if cnum == 0 {
+                krate.hash
+            } else {
+                krate.deps[cnum as usize - 1]
+            };
+            let def_crate = match crates.iter().find(|c| c.hash == hash) {
+                Some(def_crate) => def_crate,
+                None => continue,
+            };
+            let mut index = Some(index);
+            while let Some(i) = index {
+                if !used.insert((hash, i)) {
+                    break;
+                }
+                index = def_crate.def_path_table.def_key(i).parent;
+            }
+        }
+    }
+
+    for krate in &crates {
+        let mut unused: Vec<_> = krate.exported
+            .iter()
+            .filter(|&&(index, _)| !used.contains(&(krate.hash, index)))
+            .map(|&(_, ref path)| path)
+            .collect();
+        unused.sort();
+        for path in unused {
+            writeln!(out, "{}", path)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_crate_file(sess: &Session, path: &Path) -> bool {
+    let filename = match path.file_name().and_then(|f| f.to_str()) {
+        Some(filename) => filename,
+        None => return false,
+    };
+    let target = &sess.target.target.options;
+    filename.ends_with(".rlib") || filename.ends_with(".rmeta") ||
+        (filename.starts_with(&target.dll_prefix) && filename.ends_with(&target.dll_suffix))
+}
+
+// Reads the records of a crate file into `crates`, unless it has them
+// already. The files found in directories that aren't crates built with
+// `-Z record-item-uses` are skipped, but those named explicitly are warned
+// about.
+fn read_crate_uses(sess: &Session, path: &Path, explicit: bool, crates: &mut Vec<CrateUses>) {
+    let warn = |msg: &str| {
+        if explicit {
+            sess.warn(&format!("`{}` {}", path.display(), msg));
+        }
+    };
+    let blob = match locator::read_file_metadata(&sess.target.target,
+                                                 sess.codemap().file_loader(),
+                                                 path) {
+        Ok(blob) => blob,
+        Err(msg) => return warn(&format!("can't be read: {}", msg)),
+    };
+    if !is_readable(&blob) {
+        return warn("was built by a different version of rustc");
+    }
+
+    let root = blob.get_root();
+    let item_uses = match root.item_uses {
+        Some(item_uses) => item_uses.decode(&blob),
+        None => return warn("has no record of the items it uses; build it with \
+                             `-Z record-item-uses`"),
+    };
+    if crates.iter().any(|c| c.hash == root.hash) {
+        return;
+    }
+    crates.push(CrateUses {
+        hash: root.hash,
+        deps: root.crate_deps.decode(&blob).map(|dep| dep.hash).collect(),
+        def_path_table: root.def_path_table.decode(&blob),
+        exported: item_uses.exported.decode(&blob).collect(),
+        used: item_uses.used.decode(&blob).collect(),
+    });
+}
+
+fn is_readable(blob: &MetadataBlob) -> bool {
+    blob.is_compatible() && blob.get_rustc_version() == rustc_version()
+}
@@ -29,12 +29,15 @@ use rustc::util::nodemap::NodeMap;
 use syntax::ast::{self, ViewPathGlob, ViewPathList, ViewPathSimple};
 use syntax::visit::{self, Visitor};
 use syntax_pos::{Span, MultiSpan, DUMMY_SP};
+use errors::{Applicability, Diagnostic, Level};
 
 
 struct UnusedImportCheckVisitor<'a, 'b: 'a> {
     resolver: &'a mut Resolver<'b>,
     /// All the (so far) unused imports, grouped path list
     unused_imports: NodeMap<NodeMap<Span>>,
+    /// The span of every `use` item and how many imports it has
+    use_items: NodeMap<(Span, usize)>,
 }
 
 // Deref and DerefMut impls allow treating UnusedImportCheckVisitor as Resolver.
@@ -88,6 +91,11 @@ impl<'a, 'b> Visitor<'a> for UnusedImportCheckVisitor<'a, 'b> {
 
         match item.node {
             ast::ItemKind::Use(ref p) => {
+                let imports = match p.node {
+                    ViewPathList(_, ref list) if list.len() > 0 => list.len(),
+                    _ => 1,
+                };
+                self.use_items.insert(item.id, (item.span, imports));
                 match p.node {
                     ViewPathSimple(..) => {
                         self.check_import(item.id, item.id, p.span)
@@ -137,6 +145,7 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
     let mut visitor = UnusedImportCheckVisitor {
         resolver: resolver,
         unused_imports: NodeMap(),
+        use_items: NodeMap(),
     };
     visit::walk_crate(&mut visitor, krate);
 
@@ -160,9 +169,18 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
                           } else {
                               String::new()
                           });
-        visitor.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                 *id,
-                                 ms,
-                                 msg);
+        let mut diagnostic = Diagnostic::new(Level::Warning, &msg);
+        diagnostic.set_span(ms);
+        // An item none of whose imports are used can go away entirely.
+        match visitor.use_items.get(id) {
+            Some(&(item_span, imports)) if imports == len => {
+                diagnostic.span_suggestion_with_applicability(item_span,
+                                                              "remove the whole `use` item",
+                                                              String::new(),
+                                                              Applicability::MachineApplicable);
+            }
+            _ => {}
+        }
+        visitor.session.add_lint_diagnostic(lint::builtin::UNUSED_IMPORTS, *id, diagnostic);
     }
 }
@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use super::archive::{ArchiveBuilder, ArchiveConfig};
-use super::linker::Linker;
+use super::linker::{Linker, LinkerInfo};
 use super::rpath::RPathConfig;
 use super::rpath;
 use super::msvc;
@@ -222,6 +222,50 @@ pub fn link_binary(sess: &Session,
     out_filenames
 }
 
+/// Prints the command lines the linker would be run with for this crate, as
+/// requested by `--print link-args`, without translating it. The object files
+/// named are those of the `-C codegen-units` modules the translation would
+/// produce, which only differs under incremental compilation. Rlibs and
+/// static libraries are archived rather than linked, so have none.
+pub fn print_link_args(sess: &Session,
+                       outputs: &OutputFilenames,
+                       crate_name: &str,
+                       windows_subsystem: Option<String>) {
+    let objects = (0..sess.opts.cg.codegen_units).map(|i| {
+        let module = format!("{}{}{}", crate_name, config::NUMBERED_CODEGEN_UNIT_MARKER, i);
+        outputs.temp_path(OutputType::Object, Some(&module))
+    }).collect::<Vec<_>>();
+
+    let tmpdir = match TempDir::new("rustc") {
+        Ok(tmpdir) => tmpdir,
+        Err(err) => sess.fatal(&format!("couldn't create a temp dir: {}", err)),
+    };
+    let linker_info = LinkerInfo::untranslated(sess);
+
+    for &crate_type in sess.crate_types.borrow().iter() {
+        if crate_type == config::CrateTypeRlib || crate_type == config::CrateTypeStaticlib {
+            continue;
+        }
+        let out_filename = out_filename(sess, crate_type, outputs, crate_name);
+        let (_, cmd) = linker_command(sess, crate_type, &objects, &out_filename, &linker_info,
+                                      &windows_subsystem, outputs, tmpdir.path());
+        println!("{:?}", cmd);
+    }
+}
+
+/// The native libraries this crate and its upstream crates link to, leaving
+/// out those whose `cfg` doesn't hold. Upstream crates come in dependency
+/// order.
+pub fn native_libraries(sess: &Session) -> Vec<NativeLibrary> {
+    let upstream = sess.cstore.used_crates(LinkagePreference::RequireStatic)
+                              .into_iter()
+                              .flat_map(|(cnum, _)| sess.cstore.native_libraries(cnum));
+    sess.cstore.used_libraries()
+               .into_iter()
+               .chain(upstream)
+               .filter(|lib| relevant_lib(sess, lib))
+               .collect()
+}
 
 /// Returns default crate type for target
 ///
@@ -692,18 +736,16 @@ fn link_staticlib(sess: &Session, objects: &[PathBuf], out_filename: &Path,
     }
 }
 
-// Create a dynamic library or executable
-//
-// This will invoke the system linker/cc to create the resulting file. This
-// links to all upstream files as well.
-fn link_natively(sess: &Session,
-                 crate_type: config::CrateType,
-                 objects: &[PathBuf],
-                 out_filename: &Path,
-                 trans: &CrateTranslation,
-                 outputs: &OutputFilenames,
-                 tmpdir: &Path) {
-    info!("preparing {:?} from {:?} to {:?}", crate_type, objects, out_filename);
+// Build the command line invoking the system linker/cc to create a dynamic
+// library or executable, linking to all upstream files as well.
+fn linker_command(sess: &Session,
+                  crate_type: config::CrateType,
+                  objects: &[PathBuf],
+                  out_filename: &Path,
+                  linker_info: &LinkerInfo,
+                  windows_subsystem: &Option<String>,
+                  outputs: &OutputFilenames,
+                  tmpdir: &Path) -> (String, Command) {
     let flavor = sess.linker_flavor();
 
     // The invocations of cc share some flags across platforms
@@ -734,9 +776,9 @@ fn link_natively(sess: &Session,
     }
 
     {
-        let mut linker = trans.linker_info.to_linker(cmd, &sess);
+        let mut linker = linker_info.to_linker(cmd, &sess);
         link_args(&mut *linker, sess, crate_type, tmpdir,
-                  objects, out_filename, outputs, trans);
+                  objects, out_filename, outputs, windows_subsystem);
         cmd = linker.finalize();
     }
     if let Some(args) = sess.target.target.options.late_link_args.get(&flavor) {
@@ -749,6 +791,25 @@ fn link_natively(sess: &Session,
         cmd.args(args);
     }
 
+    (pname, cmd)
+}
+
+// Create a dynamic library or executable
+//
+// This will invoke the system linker/cc to create the resulting file. This
+// links to all upstream files as well.
+fn link_natively(sess: &Session,
+                 crate_type: config::CrateType,
+                 objects: &[PathBuf],
+                 out_filename: &Path,
+                 trans: &CrateTranslation,
+                 outputs: &OutputFilenames,
+                 tmpdir: &Path) {
+    info!("preparing {:?} from {:?} to {:?}", crate_type, objects, out_filename);
+    let (pname, mut cmd) = linker_command(sess, crate_type, objects, out_filename,
+                                          &trans.linker_info, &trans.windows_subsystem,
+                                          outputs, tmpdir);
+
     if sess.opts.debugging_opts.print_link_args {
         println!("{:?}", &cmd);
     }
@@ -863,7 +924,7 @@ fn link_args(cmd: &mut Linker,
              objects: &[PathBuf],
              out_filename: &Path,
              outputs: &OutputFilenames,
-             trans: &CrateTranslation) {
+             windows_subsystem: &Option<String>) {
 
     // The default library location, we need this to find the runtime.
     // The location of crates will be determined as needed.
@@ -880,7 +941,7 @@ fn link_args(cmd: &mut Linker,
 
     if crate_type == config::CrateTypeExecutable &&
        sess.target.target.options.is_like_windows {
-        if let Some(ref s) = trans.windows_subsystem {
+        if let Some(ref s) = *windows_subsystem {
             cmd.subsystem(s);
         }
     }
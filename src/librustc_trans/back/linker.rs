@@ -43,6 +43,14 @@ impl<'a, 'tcx> LinkerInfo {
         }
     }
 
+    /// Information for linking a crate that hasn't been translated, which
+    /// has no symbols to export yet.
+    pub fn untranslated(sess: &Session) -> LinkerInfo {
+        LinkerInfo {
+            exports: sess.crate_types.borrow().iter().map(|&c| (c, vec![])).collect(),
+        }
+    }
+
     pub fn to_linker(&'a self,
                      cmd: Command,
                      sess: &'a Session) -> Box<Linker+'a> {
@@ -755,6 +755,14 @@ fn write_metadata<'a, 'gcx>(tcx: TyCtxt<'a, 'gcx, 'gcx>,
         }
     }).max().unwrap();
 
+    // The uses of items are recorded in the metadata, which `--emit=metadata`
+    // then writes out for executables as well.
+    let kind = if kind == MetadataKind::None && tcx.sess.opts.debugging_opts.record_item_uses {
+        MetadataKind::Uncompressed
+    } else {
+        kind
+    };
+
     if kind == MetadataKind::None {
         return (metadata_llcx, metadata_llmod, EncodedMetadata {
             raw_data: vec![],
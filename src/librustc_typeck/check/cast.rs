@@ -49,6 +49,7 @@ use rustc::ty::cast::{CastKind, CastTy};
 use rustc::middle::lang_items;
 use syntax::ast;
 use syntax_pos::Span;
+use errors::Applicability;
 use util::common::ErrorReported;
 
 /// Reifies a cast check to be checked once we have full type information for
@@ -275,9 +276,11 @@ impl<'a, 'gcx, 'tcx> CastCheck<'tcx> {
                 if self.cast_ty.is_trait() {
                     match fcx.tcx.sess.codemap().span_to_snippet(self.cast_span) {
                         Ok(s) => {
-                            err.span_suggestion(self.cast_span,
-                                                "try casting to a reference instead:",
-                                                format!("&{}{}", mtstr, s));
+                            err.span_suggestion_with_applicability(
+                                self.cast_span,
+                                "try casting to a reference instead:",
+                                format!("&{}{}", mtstr, s),
+                                Applicability::MachineApplicable);
                         }
                         Err(_) => {
                             span_help!(err, self.cast_span, "did you mean `&{}{}`?", mtstr, tstr)
@@ -294,9 +297,11 @@ impl<'a, 'gcx, 'tcx> CastCheck<'tcx> {
             ty::TyAdt(def, ..) if def.is_box() => {
                 match fcx.tcx.sess.codemap().span_to_snippet(self.cast_span) {
                     Ok(s) => {
-                        err.span_suggestion(self.cast_span,
-                                            "try casting to a `Box` instead:",
-                                            format!("Box<{}>", s));
+                        err.span_suggestion_with_applicability(
+                            self.cast_span,
+                            "try casting to a `Box` instead:",
+                            format!("Box<{}>", s),
+                            Applicability::MachineApplicable);
                     }
                     Err(_) => span_help!(err, self.cast_span, "did you mean `Box<{}>`?", tstr),
                 }
@@ -167,14 +167,20 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
 
                 let mut err = self.type_error_struct(span,
                                                      |actual| {
-                    format!("no {} named `{}` found for type `{}` in the current scope",
-                            if mode == Mode::MethodCall {
-                                "method"
-                            } else {
-                                "associated item"
-                            },
-                            item_name,
-                            actual)
+                    let msg = if mode == Mode::MethodCall {
+                        diagnostic_message!("typeck-no-method",
+                                            "no method named `{name}` found for type `{ty}` \
+                                             in the current scope",
+                                            name = item_name.to_string(),
+                                            ty = actual)
+                    } else {
+                        diagnostic_message!("typeck-no-associated-item",
+                                            "no associated item named `{name}` found for type \
+                                             `{ty}` in the current scope",
+                                            name = item_name.to_string(),
+                                            ty = actual)
+                    };
+                    tcx.sess.translate(&msg)
                 },
                                                      rcvr_ty);
 
@@ -280,19 +280,23 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 if l_ty.ty.sty == TyStr && r_ty.ty.sty == TyStr {
                     err.note("`+` can't be used to concatenate two `&str` strings");
                     let codemap = self.tcx.sess.codemap();
-                    let suggestion =
+                    let (suggestion, applicability) =
                         match (codemap.span_to_snippet(lhs_expr.span),
                                 codemap.span_to_snippet(rhs_expr.span)) {
                             (Ok(lstring), Ok(rstring)) =>
-                                format!("{}.to_owned() + {}", lstring, rstring),
-                            _ => format!("<expression>")
+                                (format!("{}.to_owned() + {}", lstring, rstring),
+                                 errors::Applicability::MachineApplicable),
+                            _ => (format!("<expression>"),
+                                  errors::Applicability::HasPlaceholders),
                         };
-                    err.span_suggestion(expr.span,
+                    err.span_suggestion_with_applicability(expr.span,
                         &format!("to_owned() can be used to create an owned `String` \
                                   from a string reference. String concatenation \
                                   appends the string on the right to the string \
                                   on the left and may require reallocation. This \
-                                  requires ownership of the string on the left."), suggestion);
+                                  requires ownership of the string on the left."),
+                        suggestion,
+                        applicability);
                     is_string_addition = true;
                 }
 
@@ -1001,6 +1001,7 @@ impl<T> Receiver<T> {
     /// assert_eq!(Err(RecvError), recv.recv());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_blocking)]
     pub fn recv(&self) -> Result<T, RecvError> {
         loop {
             let new_port = match *unsafe { self.inner() } {
@@ -1064,6 +1065,7 @@ impl<T> Receiver<T> {
     /// assert_eq!(Err(RecvTimeoutError::Timeout), recv.recv_timeout(timeout));
     /// ```
     #[stable(feature = "mpsc_recv_timeout", since = "1.12.0")]
+    #[cfg_attr(not(stage0), rustc_blocking)]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
         // Do an optimistic try_recv to avoid the performance impact of
         // Instant::now() in the full-channel case.
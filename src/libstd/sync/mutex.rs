@@ -216,6 +216,7 @@ impl<T: ?Sized> Mutex<T> {
     /// assert_eq!(*mutex.lock().unwrap(), 10);
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_lock)]
     pub fn lock(&self) -> LockResult<MutexGuard<T>> {
         unsafe {
             self.inner.lock();
@@ -156,6 +156,7 @@ impl<T: ?Sized> RwLock<T> {
     /// This function might panic when called if the lock is already held by the current thread.
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_shared_lock)]
     pub fn read(&self) -> LockResult<RwLockReadGuard<T>> {
         unsafe {
             self.inner.read();
@@ -212,6 +213,7 @@ impl<T: ?Sized> RwLock<T> {
     /// This function might panic when called if the lock is already held by the current thread.
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_lock)]
     pub fn write(&self) -> LockResult<RwLockWriteGuard<T>> {
         unsafe {
             self.inner.write();
@@ -976,6 +976,7 @@ impl<T> JoinHandle<T> {
     /// join_handle.join().expect("Couldn't join on the associated thread");
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_blocking)]
     pub fn join(mut self) -> Result<T> {
         self.0.join()
     }
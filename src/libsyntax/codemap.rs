@@ -61,7 +61,16 @@ pub fn dummy_spanned<T>(t: T) -> Spanned<T> {
 // FileMap, MultiByteChar, FileName, FileLines
 //
 
-/// An abstraction over the fs operations used by the Parser.
+/// An abstraction over the fs operations used by the compiler: loading
+/// source files and modules, `include!` and friends, and searching for and
+/// reading the metadata of crates.
+///
+/// Only the first three methods need to be given. By default, directories
+/// are listed from the real file system, so that libraries are found in the
+/// search paths and the sysroot, and files that exist on disk are mapped
+/// from there. Other files are read through `read_file`, so loaders that
+/// hold files of their own, binary ones in particular, should give the
+/// other methods too.
 pub trait FileLoader {
     /// Query the existence of a file.
     fn file_exists(&self, path: &Path) -> bool;
@@ -71,6 +80,39 @@ pub trait FileLoader {
 
     /// Read the contents of an UTF-8 file into memory.
     fn read_file(&self, path: &Path) -> io::Result<String>;
+
+    /// Read the contents of a file, which need not be UTF-8, into memory.
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.read_file(path).map(String::into_bytes)
+    }
+
+    /// Return the paths of the entries of a directory.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        RealFileLoader.read_dir(path)
+    }
+
+    /// Return the canonical, absolute form of the path of a file.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if let Ok(canonical) = RealFileLoader.canonicalize(path) {
+            return Ok(canonical);
+        }
+        if !self.file_exists(path) {
+            return Err(io::Error::new(io::ErrorKind::NotFound,
+                                      format!("`{}` doesn't exist", path.display())));
+        }
+        self.abs_path(path).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other,
+                           format!("`{}` has no absolute path", path.display()))
+        })
+    }
+
+    /// Return the path of a file on disk holding the contents of `path`, if
+    /// there is one. This lets large files like rlibs be mapped into memory
+    /// instead of being copied. If there is none, the contents are read with
+    /// `read_binary_file`.
+    fn real_path(&self, path: &Path) -> Option<PathBuf> {
+        RealFileLoader.real_path(path)
+    }
 }
 
 /// A FileLoader that uses std::fs to load real files.
@@ -96,6 +138,28 @@ impl FileLoader for RealFileLoader {
         fs::File::open(path)?.read_to_string(&mut src)?;
         Ok(src)
     }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?.map(|entry| entry.map(|e| e.path())).collect()
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn real_path(&self, path: &Path) -> Option<PathBuf> {
+        if self.file_exists(path) {
+            Some(path.to_path_buf())
+        } else {
+            None
+        }
+    }
 }
 
 // _____________________________________________________________________________
@@ -122,6 +186,11 @@ impl CodeMap {
         }
     }
 
+    /// The loader all files used by the compilation are read through.
+    pub fn file_loader(&self) -> &FileLoader {
+        &*self.file_loader
+    }
+
     pub fn file_exists(&self, path: &Path) -> bool {
         self.file_loader.file_exists(path)
     }
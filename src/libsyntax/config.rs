@@ -119,6 +119,7 @@ impl<'a> StripUnconfigured<'a> {
     pub fn in_cfg(&mut self, attrs: &[ast::Attribute]) -> bool {
         attrs.iter().all(|attr| {
             // When not compiling with --test we should not compile the #[test] functions
+            // (or #[test_case] items)
             if !self.should_test && is_test_or_bench(attr) {
                 return false;
             }
@@ -359,5 +360,5 @@ fn is_cfg(attr: &ast::Attribute) -> bool {
 }
 
 pub fn is_test_or_bench(attr: &ast::Attribute) -> bool {
-    attr.check_name("test") || attr.check_name("bench")
+    attr.check_name("test") || attr.check_name("bench") || attr.check_name("test_case")
 }
@@ -86,6 +86,25 @@ macro_rules! struct_span_err_or_warn {
     })
 }
 
+/// Builds a message that can be translated: its ID in diagnostic catalogs,
+/// its text in English, and the values of the `{name}` placeholders in it.
+#[macro_export]
+macro_rules! diagnostic_message {
+    ($id:expr, $fallback:expr $(, $name:ident = $value:expr)*) => (
+        $crate::errors::catalog::Message::new($id, $fallback)$(.arg(stringify!($name), $value))*
+    )
+}
+
+/// Like `struct_span_err!`, with a message built by `diagnostic_message!`.
+#[macro_export]
+macro_rules! struct_span_err_translated {
+    ($session:expr, $span:expr, $code:ident, $message:expr) => ({
+        __diagnostic_used!($code);
+        let message = $session.translate(&$message);
+        $session.struct_span_err_with_code($span, &message, stringify!($code))
+    })
+}
+
 #[macro_export]
 macro_rules! span_note {
     ($err:expr, $span:expr, $($message:tt)*) => ({
@@ -21,8 +21,6 @@ use symbol::Symbol;
 use tokenstream;
 use util::small_vector::SmallVector;
 
-use std::fs::File;
-use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
@@ -126,9 +124,8 @@ pub fn expand_include_str(cx: &mut ExtCtxt, sp: Span, tts: &[tokenstream::TokenT
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, Path::new(&file));
-    let mut bytes = Vec::new();
-    match File::open(&file).and_then(|mut f| f.read_to_end(&mut bytes)) {
-        Ok(..) => {}
+    let bytes = match cx.codemap().file_loader().read_binary_file(&file) {
+        Ok(bytes) => bytes,
         Err(e) => {
             cx.span_err(sp,
                         &format!("couldn't read {}: {}",
@@ -162,14 +159,13 @@ pub fn expand_include_bytes(cx: &mut ExtCtxt, sp: Span, tts: &[tokenstream::Toke
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, Path::new(&file));
-    let mut bytes = Vec::new();
-    match File::open(&file).and_then(|mut f| f.read_to_end(&mut bytes)) {
+    match cx.codemap().file_loader().read_binary_file(&file) {
         Err(e) => {
             cx.span_err(sp,
                         &format!("couldn't read {}: {}", file.display(), e));
             return DummyResult::expr(sp);
         }
-        Ok(..) => {
+        Ok(bytes) => {
             // Add this input file to the code map to make it available as
             // dependency information, but don't enter it's contents
             let filename = format!("{}", file.display());
@@ -355,6 +355,15 @@ declare_features! (
 
     // Allows use of the :vis macro fragment specifier
     (active, macro_vis_matcher, "1.18.0", Some(41022)),
+
+    // Allows `#[timeout = "N"]` on tests
+    (active, test_timeout, "1.18.0", None),
+
+    // Allows `#![test_runner = "..."]` and `#[test_case]`
+    (active, custom_test_frameworks, "1.18.0", None),
+
+    // Allows `#[must_use]` on functions and methods
+    (active, fn_must_use, "1.18.0", None),
 );
 
 declare_features! (
@@ -598,6 +607,25 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_lock", Whitelisted, Gated(Stability::Unstable,
+                                      "rustc_attrs",
+                                      "the `#[rustc_lock]` attribute \
+                                       marks the functions that take a lock \
+                                       and will never be stable",
+                                      cfg_fn!(rustc_attrs))),
+    ("rustc_shared_lock", Whitelisted, Gated(Stability::Unstable,
+                                             "rustc_attrs",
+                                             "the `#[rustc_shared_lock]` attribute \
+                                              marks the functions that take a lock \
+                                              shared with other readers \
+                                              and will never be stable",
+                                             cfg_fn!(rustc_attrs))),
+    ("rustc_blocking", Whitelisted, Gated(Stability::Unstable,
+                                          "rustc_attrs",
+                                          "the `#[rustc_blocking]` attribute \
+                                           marks the functions that block the thread \
+                                           and will never be stable",
+                                          cfg_fn!(rustc_attrs))),
     ("rustc_error", Whitelisted, Gated(Stability::Unstable,
                                        "rustc_attrs",
                                        "the `#[rustc_error]` attribute \
@@ -766,6 +794,18 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
         Stability::Unstable, "used",
         "the `#[used]` attribute is an experimental feature",
         cfg_fn!(used))),
+    ("timeout", Normal, Gated(
+        Stability::Unstable, "test_timeout",
+        "the `#[timeout]` attribute is an experimental feature",
+        cfg_fn!(test_timeout))),
+    ("test_runner", CrateLevel, Gated(
+        Stability::Unstable, "custom_test_frameworks",
+        "custom test runners are an experimental feature",
+        cfg_fn!(custom_test_frameworks))),
+    ("test_case", Whitelisted, Gated(
+        Stability::Unstable, "custom_test_frameworks",
+        "custom test cases are an experimental feature",
+        cfg_fn!(custom_test_frameworks))),
 
     // used in resolve
     ("prelude_import", Whitelisted, Gated(Stability::Unstable,
@@ -1172,6 +1212,10 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                                         function may change over time, for now \
                                         a top-level `fn main()` is required");
                 }
+                if attr::contains_name(&i.attrs[..], "must_use") {
+                    gate_feature_post!(&self, fn_must_use, i.span,
+                                       "`#[must_use]` on functions is experimental");
+                }
             }
 
             ast::ItemKind::Struct(..) => {
@@ -1403,6 +1447,10 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                 if sig.constness.node == ast::Constness::Const {
                     gate_feature_post!(&self, const_fn, ti.span, "const fn is unstable");
                 }
+                if attr::contains_name(&ti.attrs[..], "must_use") {
+                    gate_feature_post!(&self, fn_must_use, ti.span,
+                                       "`#[must_use]` on methods is experimental");
+                }
             }
             ast::TraitItemKind::Type(_, Some(_)) => {
                 gate_feature_post!(&self, associated_type_defaults, ti.span,
@@ -1430,6 +1478,10 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                 if sig.constness.node == ast::Constness::Const {
                     gate_feature_post!(&self, const_fn, ii.span, "const fn is unstable");
                 }
+                if attr::contains_name(&ii.attrs[..], "must_use") {
+                    gate_feature_post!(&self, fn_must_use, ii.span,
+                                       "`#[must_use]` on methods is experimental");
+                }
             }
             _ => {}
         }
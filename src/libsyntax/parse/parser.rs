@@ -42,7 +42,7 @@ use ast::RangeEnd;
 use {ast, attr};
 use codemap::{self, CodeMap, Spanned, respan};
 use syntax_pos::{self, Span, BytePos};
-use errors::{self, DiagnosticBuilder};
+use errors::{self, Applicability, DiagnosticBuilder};
 use parse::{self, classify, token};
 use parse::common::SeqSep;
 use parse::lexer::TokenAndSpan;
@@ -1508,7 +1508,10 @@ impl<'a> Parser<'a> {
                     s.print_bounds(" +", &bounds)?;
                     s.pclose()
                 });
-                err.span_suggestion(sum_span, "try adding parentheses:", sum_with_parens);
+                err.span_suggestion_with_applicability(sum_span,
+                                                       "try adding parentheses:",
+                                                       sum_with_parens,
+                                                       Applicability::MachineApplicable);
             }
             TyKind::Ptr(..) | TyKind::BareFn(..) => {
                 help!(&mut err, "perhaps you forgot parentheses?");
@@ -2570,10 +2573,11 @@ impl<'a> Parser<'a> {
                             word(&mut s.s, ".")?;
                             word(&mut s.s, fstr.splitn(2, ".").last().unwrap())
                         });
-                        err.span_suggestion(
+                        err.span_suggestion_with_applicability(
                             lo.to(self.prev_span),
                             "try parenthesizing the first index",
-                            sugg);
+                            sugg,
+                            Applicability::MachineApplicable);
                     }
                     return Err(err);
 
@@ -3984,7 +3988,10 @@ impl<'a> Parser<'a> {
                         s.print_stmt(&stmt)?;
                         s.bclose_maybe_open(stmt.span, INDENT_UNIT, false)
                     });
-                    e.span_suggestion(stmt_span, "try placing this code inside a block", sugg);
+                    e.span_suggestion_with_applicability(stmt_span,
+                                                         "try placing this code inside a block",
+                                                         sugg,
+                                                         Applicability::MaybeIncorrect);
                 }
                 Err(mut e) => {
                     self.recover_stmt_(SemiColonMode::Break, BlockMode::Ignore);
@@ -5167,7 +5174,10 @@ impl<'a> Parser<'a> {
                 self.expect(&token::CloseDelim(token::Paren))?;  // `)`
                 let sp = start_span.to(self.prev_span);
                 let mut err = self.span_fatal_help(sp, &msg, &suggestion);
-                err.span_suggestion(path_span, &help_msg, format!("in {}", path));
+                err.span_suggestion_with_applicability(path_span,
+                                                       &help_msg,
+                                                       format!("in {}", path),
+                                                       Applicability::MaybeIncorrect);
                 err.emit();  // emit diagnostic, but continue with public visibility
             }
         }
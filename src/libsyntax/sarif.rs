@@ -0,0 +1,283 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A SARIF emitter for errors (`--error-format=sarif`).
+//!
+//! SARIF (the Static Analysis Results Interchange Format, version 2.1.0) is
+//! the format consumed by code scanning tools. Unlike the JSON emitter, which
+//! writes each diagnostic as it comes, this one gathers the diagnostics of the
+//! whole compilation into the results of a single run, and writes out the
+//! document when it is dropped.
+//!
+//! Every diagnostic becomes a result located at its primary spans. Secondary
+//! spans and sub-diagnostics with spans of their own become related
+//! locations, suggestions become fixes, and error codes become rules linking
+//! to their explanation.
+
+use codemap::CodeMap;
+use syntax_pos::{MultiSpan, Span};
+use errors::registry::Registry;
+use errors::{DiagnosticBuilder, Level, RenderSpan, CodeSuggestion, CodeMapper};
+use errors::emitter::Emitter;
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use rustc_serialize::json::{Json, ToJson};
+
+const SCHEMA: &'static str = "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0.json";
+
+const ERROR_INDEX: &'static str = "https://doc.rust-lang.org/error-index.html";
+
+pub struct SarifEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    cm: Rc<CodeMapper + 'static>,
+    /// The error codes seen so far, in the order they became rules.
+    rules: Vec<String>,
+    results: Vec<Json>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>,
+                  code_map: Rc<CodeMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), registry, code_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        SarifEmitter::stderr(None, Rc::new(CodeMap::new()))
+    }
+
+    pub fn new(dst: Box<Write + Send>,
+               registry: Option<Registry>,
+               code_map: Rc<CodeMap>) -> SarifEmitter {
+        SarifEmitter {
+            dst: dst,
+            registry: registry,
+            cm: code_map,
+            rules: vec![],
+            results: vec![],
+        }
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let level = match sarif_level(db.level) {
+            Some(level) => level,
+            None => return,
+        };
+
+        let mut result = vec![
+            ("level", level.to_json()),
+        ];
+        if let Some(ref code) = db.code {
+            let index = self.rule_index(code);
+            result.push(("ruleId", code.to_json()));
+            result.push(("ruleIndex", index.to_json()));
+        }
+
+        // Sub-diagnostics without a span can only be told as part of the
+        // message, the others are related locations.
+        let mut message = db.message();
+        let mut related = vec![];
+        let mut fixes = vec![];
+        for span_label in db.span.span_labels() {
+            if span_label.is_primary {
+                continue;
+            }
+            if let Some(location) = self.location(span_label.span, span_label.label) {
+                related.push(location);
+            }
+        }
+        for child in &db.children {
+            let child_message = format!("{}: {}", child.level, child.message());
+            match child.render_span {
+                Some(RenderSpan::Suggestion(ref suggestion)) => {
+                    if let Some(fix) = self.fix(suggestion, child.message()) {
+                        fixes.push(fix);
+                    }
+                }
+                Some(RenderSpan::FullSpan(ref msp)) => {
+                    related.extend(self.locations(msp, &child_message));
+                }
+                None if !child.span.primary_spans().is_empty() => {
+                    related.extend(self.locations(&child.span, &child_message));
+                }
+                None => {
+                    message.push('\n');
+                    message.push_str(&child_message);
+                }
+            }
+        }
+
+        result.push(("message", object(vec![("text", message.to_json())])));
+        let locations = db.span.primary_spans()
+                               .iter()
+                               .filter_map(|&span| self.location(span, None))
+                               .collect::<Vec<_>>();
+        if !locations.is_empty() {
+            result.push(("locations", Json::Array(locations)));
+        }
+        if !related.is_empty() {
+            for (id, location) in related.iter_mut().enumerate() {
+                if let Json::Object(ref mut location) = *location {
+                    location.insert("id".to_owned(), id.to_json());
+                }
+            }
+            result.push(("relatedLocations", Json::Array(related)));
+        }
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+        self.results.push(object(result));
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let mut driver = vec![
+            ("name", "rustc".to_json()),
+            ("informationUri", "https://www.rust-lang.org/".to_json()),
+            ("rules", Json::Array(self.rules.iter().map(|code| self.rule(code)).collect())),
+        ];
+        if let Some(version) = option_env!("CFG_VERSION") {
+            driver.push(("version", version.to_json()));
+        }
+        let run = object(vec![
+            ("tool", object(vec![("driver", object(driver))])),
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", Json::Array(self.results.drain(..).collect())),
+        ]);
+        let document = object(vec![
+            ("$schema", SCHEMA.to_json()),
+            ("version", "2.1.0".to_json()),
+            ("runs", Json::Array(vec![run])),
+        ]);
+        // There is nobody left to tell about a failure to write this out.
+        let _ = writeln!(&mut self.dst, "{}", document.pretty());
+    }
+}
+
+impl SarifEmitter {
+    fn rule_index(&mut self, code: &str) -> usize {
+        match self.rules.iter().position(|c| c == code) {
+            Some(index) => index,
+            None => {
+                self.rules.push(code.to_owned());
+                self.rules.len() - 1
+            }
+        }
+    }
+
+    fn rule(&self, code: &str) -> Json {
+        let mut rule = vec![
+            ("id", code.to_json()),
+            ("helpUri", format!("{}#{}", ERROR_INDEX, code).to_json()),
+        ];
+        let explanation = self.registry
+                              .as_ref()
+                              .and_then(|registry| registry.find_description(code));
+        if let Some(explanation) = explanation {
+            rule.push(("fullDescription", object(vec![
+                ("text", explanation.trim().to_json()),
+                ("markdown", explanation.trim().to_json()),
+            ])));
+        }
+        object(rule)
+    }
+
+    fn locations(&self, msp: &MultiSpan, message: &str) -> Vec<Json> {
+        msp.primary_spans()
+           .iter()
+           .filter_map(|&span| self.location(span, Some(message.to_owned())))
+           .collect()
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Json> {
+        let physical = match self.physical_location(span) {
+            Some(physical) => physical,
+            None => return None,
+        };
+        let mut location = vec![("physicalLocation", physical)];
+        if let Some(message) = message {
+            location.push(("message", object(vec![("text", message.to_json())])));
+        }
+        Some(object(location))
+    }
+
+    fn physical_location(&self, span: Span) -> Option<Json> {
+        let (artifact, region) = match self.artifact_and_region(span) {
+            Some(location) => location,
+            None => return None,
+        };
+        Some(object(vec![
+            ("artifactLocation", artifact),
+            ("region", region),
+        ]))
+    }
+
+    fn artifact_and_region(&self, span: Span) -> Option<(Json, Json)> {
+        let lo = self.cm.lookup_char_pos(span.lo);
+        if !lo.file.is_real_file() {
+            return None;
+        }
+        let hi = self.cm.lookup_char_pos(span.hi);
+        // Lines are 1-based in both, columns are 0-based in `Loc`.
+        let region = object(vec![
+            ("startLine", lo.line.to_json()),
+            ("startColumn", (lo.col.0 + 1).to_json()),
+            ("endLine", hi.line.to_json()),
+            ("endColumn", (hi.col.0 + 1).to_json()),
+        ]);
+        let uri = lo.file.name.replace("\\", "/");
+        Some((object(vec![("uri", uri.to_json())]), region))
+    }
+
+    fn fix(&self, suggestion: &CodeSuggestion, description: String) -> Option<Json> {
+        let mut changes = vec![];
+        for (&span, substitute) in suggestion.msp.primary_spans()
+                                             .iter()
+                                             .zip(&suggestion.substitutes) {
+            let (artifact, region) = match self.artifact_and_region(span) {
+                Some(location) => location,
+                None => return None,
+            };
+            let replacement = object(vec![
+                ("deletedRegion", region),
+                ("insertedContent", object(vec![("text", substitute.to_json())])),
+            ]);
+            changes.push(object(vec![
+                ("artifactLocation", artifact),
+                ("replacements", Json::Array(vec![replacement])),
+            ]));
+        }
+        Some(object(vec![
+            ("description", object(vec![("text", description.to_json())])),
+            ("artifactChanges", Json::Array(changes)),
+        ]))
+    }
+}
+
+fn sarif_level(level: Level) -> Option<&'static str> {
+    match level {
+        Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => Some("error"),
+        Level::Warning => Some("warning"),
+        Level::Note | Level::Help => Some("note"),
+        Level::Cancelled => None,
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter()
+                       .map(|(key, value)| (key.to_owned(), value))
+                       .collect::<BTreeMap<_, _>>())
+}
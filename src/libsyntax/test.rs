@@ -52,7 +52,14 @@ struct Test {
     path: Vec<Ident> ,
     bench: bool,
     ignore: bool,
-    should_panic: ShouldPanic
+    should_panic: ShouldPanic,
+    timeout: Option<u64>,
+}
+
+// A `#[test_case]` item, handed to a custom test runner as is
+struct TestCase {
+    span: Span,
+    path: Vec<Ident>,
 }
 
 struct TestCtxt<'a> {
@@ -61,7 +68,10 @@ struct TestCtxt<'a> {
     path: Vec<Ident>,
     ext_cx: ExtCtxt<'a>,
     testfns: Vec<Test>,
+    test_cases: Vec<TestCase>,
     reexport_test_harness_main: Option<Symbol>,
+    // path to the function the harness should call instead of `test::test_main_static`
+    test_runner: Option<(Span, Vec<Ident>)>,
     is_test_crate: bool,
     ctxt: SyntaxContext,
 
@@ -84,8 +94,25 @@ pub fn modify_for_testing(sess: &ParseSess,
         attr::first_attr_value_str_by_name(&krate.attrs,
                                            "reexport_test_harness_main");
 
+    // Likewise for #![test_runner = "path::to::runner"], which names a function
+    // taking a slice of the crate's tests, to be called instead of libtest's.
+    let test_runner = krate.attrs.iter()
+                               .find(|attr| attr.check_name("test_runner"))
+                               .map(|attr| (attr.span, attr.value_str()));
+
     if should_test {
-        generate_test_harness(sess, resolver, reexport_test_harness_main, krate, span_diagnostic)
+        let test_runner = test_runner.and_then(|(span, path)| {
+            match path.and_then(|path| parse_runner_path(&path.as_str())) {
+                Some(path) => Some((span, path)),
+                None => {
+                    span_diagnostic.span_err(span, "attribute must be of the form: \
+                                                    `#![test_runner = \"path::to::runner\"]`");
+                    None
+                }
+            }
+        });
+        generate_test_harness(sess, resolver, reexport_test_harness_main, test_runner, krate,
+                              span_diagnostic)
     } else {
         krate
     }
@@ -134,12 +161,20 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
                         path: self.cx.path.clone(),
                         bench: is_bench_fn(&self.cx, &i),
                         ignore: is_ignored(&i),
-                        should_panic: should_panic(&i, &self.cx)
+                        should_panic: should_panic(&i, &self.cx),
+                        timeout: test_timeout(&i, &self.cx),
                     };
                     self.cx.testfns.push(test);
                     self.tests.push(i.ident);
                 }
             }
+        } else if is_test_case(&self.cx, &i) {
+            debug!("this is a test case");
+            self.cx.test_cases.push(TestCase {
+                span: i.span,
+                path: self.cx.path.clone(),
+            });
+            self.tests.push(i.ident);
         }
 
         let mut item = i.unwrap();
@@ -271,6 +306,7 @@ fn mk_reexport_mod(cx: &mut TestCtxt,
 fn generate_test_harness(sess: &ParseSess,
                          resolver: &mut Resolver,
                          reexport_test_harness_main: Option<Symbol>,
+                         test_runner: Option<(Span, Vec<Ident>)>,
                          krate: ast::Crate,
                          sd: &errors::Handler) -> ast::Crate {
     // Remove the entry points
@@ -284,7 +320,9 @@ fn generate_test_harness(sess: &ParseSess,
         ext_cx: ExtCtxt::new(sess, ExpansionConfig::default("test".to_string()), resolver),
         path: Vec::new(),
         testfns: Vec::new(),
+        test_cases: Vec::new(),
         reexport_test_harness_main: reexport_test_harness_main,
+        test_runner: test_runner,
         is_test_crate: is_test_crate(&krate),
         toplevel_reexport: None,
         ctxt: SyntaxContext::empty().apply_mark(mark),
@@ -388,6 +426,35 @@ fn is_bench_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
     return has_bench_attr && has_test_signature(i);
 }
 
+fn is_test_case(cx: &TestCtxt, i: &ast::Item) -> bool {
+    if !attr::contains_name(&i.attrs, "test_case") {
+        return false;
+    }
+    match i.node {
+        ast::ItemKind::Fn(..) | ast::ItemKind::Const(..) | ast::ItemKind::Static(..) => true,
+        _ => {
+            cx.span_diagnostic.span_err(i.span, "only functions, constants and statics may \
+                                                 be used as test cases");
+            false
+        }
+    }
+}
+
+// Splits the value of `#![test_runner]` into the path it names
+fn parse_runner_path(path: &str) -> Option<Vec<Ident>> {
+    let path = if path.starts_with("::") { &path[2..] } else { path };
+    path.split("::").map(|segment| {
+        let is_ident = segment.chars().enumerate().all(|(i, c)| {
+            c == '_' || c.is_alphabetic() || (i > 0 && c.is_numeric())
+        });
+        if is_ident && !segment.is_empty() && segment != "_" {
+            Some(Ident::from_str(segment))
+        } else {
+            None
+        }
+    }).collect()
+}
+
 fn is_ignored(i: &ast::Item) -> bool {
     i.attrs.iter().any(|attr| attr.check_name("ignore"))
 }
@@ -435,6 +502,23 @@ fn should_panic(i: &ast::Item, cx: &TestCtxt) -> ShouldPanic {
     }
 }
 
+fn test_timeout(i: &ast::Item, cx: &TestCtxt) -> Option<u64> {
+    let attr = match i.attrs.iter().find(|attr| attr.check_name("timeout")) {
+        Some(attr) => attr,
+        None => return None,
+    };
+    match attr.value_str().and_then(|s| s.as_str().parse::<u64>().ok()) {
+        Some(secs) if secs > 0 => Some(secs),
+        _ => {
+            cx.span_diagnostic.span_err(attr.span(),
+                                        "attribute must be of the form: \
+                                         `#[timeout = \"N\"]`, where N is a positive \
+                                         number of seconds");
+            None
+        }
+    }
+}
+
 /*
 
 We're going to be building a module that looks more or less like:
@@ -450,6 +534,12 @@ mod __test {
   ];
 }
 
+With a custom `#![test_runner = "runner"]`, `main` instead calls
+
+    ::runner(&[&tests[0], ..., &tests[n - 1], &::path::to::test_case, ...])
+
+so the runner can take `&[&Trait]` for a trait of its own choosing.
+
 */
 
 fn mk_std(cx: &TestCtxt) -> P<ast::Item> {
@@ -484,15 +574,34 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
     let sp = ignored_span(cx, DUMMY_SP);
     let ecx = &cx.ext_cx;
 
-    // test::test_main_static
-    let test_main_path =
-        ecx.path(sp, vec![Ident::from_str("test"), Ident::from_str("test_main_static")]);
-
-    // test::test_main_static(...)
-    let test_main_path_expr = ecx.expr_path(test_main_path);
     let tests_ident_expr = ecx.expr_ident(sp, Ident::from_str("TESTS"));
-    let call_test_main = ecx.expr_call(sp, test_main_path_expr,
-                                       vec![tests_ident_expr]);
+    let call_test_main = match cx.test_runner {
+        Some((runner_sp, ref runner)) => {
+            // ::path::to::runner(&[&TESTS[0], ..., &::__test_reexports::a_test_case, ...])
+            let runner_sp = ignored_span(cx, runner_sp);
+            let runner_path_expr = ecx.expr_path(ecx.path_global(runner_sp, runner.clone()));
+            let tests = (0..cx.testfns.len()).map(|i| {
+                let index = ecx.expr_usize(sp, i);
+                ecx.expr_addr_of(sp, ecx.expr(sp, ast::ExprKind::Index(tests_ident_expr.clone(),
+                                                                       index)))
+            });
+            let test_cases = cx.test_cases.iter().map(|case| {
+                let case_sp = ignored_span(cx, case.span);
+                ecx.expr_addr_of(case_sp, ecx.expr_path(visible_path(cx, case_sp, &case.path)))
+            });
+            let all_tests = ecx.expr_vec_slice(sp, tests.chain(test_cases).collect());
+            ecx.expr_call(runner_sp, runner_path_expr, vec![all_tests])
+        }
+        None => {
+            // test::test_main_static
+            let test_main_path =
+                ecx.path(sp, vec![Ident::from_str("test"), Ident::from_str("test_main_static")]);
+
+            // test::test_main_static(...)
+            let test_main_path_expr = ecx.expr_path(test_main_path);
+            ecx.expr_call(sp, test_main_path_expr, vec![tests_ident_expr])
+        }
+    };
     let call_test_main = ecx.stmt_expr(call_test_main);
     // #![main]
     let main_meta = ecx.meta_word(sp, Symbol::intern("main"));
@@ -517,6 +626,14 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
 }
 
 fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<P<ast::Item>>) {
+    if cx.test_runner.is_none() {
+        for case in &cx.test_cases {
+            cx.span_diagnostic.span_err(case.span, "test cases can only be run by a custom \
+                                                    test runner, named with \
+                                                    `#![test_runner = \"...\"]`");
+        }
+    }
+
     // Link to test crate
     let import = mk_std(cx);
 
@@ -633,6 +750,20 @@ fn mk_test_descs(cx: &TestCtxt) -> P<ast::Expr> {
     })
 }
 
+// The path to a test through the re-export modules, e.g.
+// `::__test_reexports::foo::__test_reexports::bar`
+fn visible_path(cx: &TestCtxt, span: Span, path: &[Ident]) -> ast::Path {
+    let mut visible_path = match cx.toplevel_reexport {
+        Some(id) => vec![id],
+        None => {
+            let diag = cx.span_diagnostic;
+            diag.bug("expected to find top-level re-export name, but found None");
+        }
+    };
+    visible_path.extend(path.iter().cloned());
+    cx.ext_cx.path_global(span, visible_path)
+}
+
 fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
     // FIXME #15962: should be using quote_expr, but that stringifies
     // __test_reexports, causing it to be reinterned, losing the
@@ -680,25 +811,26 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         }
     };
 
+    let timeout_expr = match test.timeout {
+        Some(secs) => {
+            let secs = ecx.expr_lit(span, ast::LitKind::Int(secs as u128,
+                                                             ast::LitIntType::Unsuffixed));
+            ecx.expr_some(span, secs)
+        }
+        None => ecx.expr_none(span),
+    };
+
     // self::test::TestDesc { ... }
     let desc_expr = ecx.expr_struct(
         span,
         test_path("TestDesc"),
         vec![field("name", name_expr),
              field("ignore", ignore_expr),
-             field("should_panic", fail_expr)]);
-
+             field("should_panic", fail_expr),
+             field("timeout", timeout_expr)]);
 
-    let mut visible_path = match cx.toplevel_reexport {
-        Some(id) => vec![id],
-        None => {
-            let diag = cx.span_diagnostic;
-            diag.bug("expected to find top-level re-export name, but found None");
-        }
-    };
-    visible_path.extend(path);
 
-    let fn_expr = ecx.expr_path(ecx.path_global(span, visible_path));
+    let fn_expr = ecx.expr_path(visible_path(cx, span, &path));
 
     let variant_name = if test.bench { "StaticBenchFn" } else { "StaticTestFn" };
     // self::test::$variant_name($fn_expr)
@@ -0,0 +1,211 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Benchmark baselines (`--save-baseline` and `--baseline`).
+//!
+//! A baseline keeps the per-iteration samples of every benchmark of a test
+//! binary, so that a later run can tell whether a benchmark really changed or
+//! whether the difference is just noise. Baselines live in
+//! `$RUST_BENCH_BASELINE_DIR/<name>/<binary>.json`, the directory defaulting
+//! to `bench-baselines` next to the test binary.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serialize::json::{self, Json, ToJson};
+use stats::{self, Stats};
+
+/// Changes with a p-value below this are considered real.
+const SIGNIFICANCE: f64 = 0.05;
+
+/// Changes of the median smaller than this many percent are never reported,
+/// however significant.
+const NOISE_PCT: f64 = 2.0;
+
+pub struct Baseline {
+    path: PathBuf,
+    benches: BTreeMap<String, Vec<f64>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+}
+
+/// How a benchmark compares to its baseline.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Comparison {
+    /// Change of the median time per iteration, in percent.
+    pub change_pct: f64,
+    /// Probability of a difference at least this large arising by chance.
+    pub p_value: f64,
+    pub verdict: Verdict,
+}
+
+impl Baseline {
+    /// Loads the baseline called `name`, failing if it hasn't been saved yet.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(name)?;
+        let mut contents = String::new();
+        File::open(&path)
+            .and_then(|mut f| f.read_to_string(&mut contents))
+            .map_err(|e| io::Error::new(e.kind(),
+                                        format!("cannot read baseline `{}` from {}: {}",
+                                                name, path.display(), e)))?;
+        let benches = parse(&contents).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData,
+                           format!("baseline `{}` at {} is malformed", name, path.display()))
+        })?;
+        Ok(Baseline {
+            path: path,
+            benches: benches,
+        })
+    }
+
+    /// Loads the baseline called `name` for updating, starting afresh if it
+    /// doesn't exist yet.
+    pub fn load_or_new(name: &str) -> io::Result<Baseline> {
+        match Baseline::load(name) {
+            Ok(baseline) => Ok(baseline),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                Ok(Baseline {
+                    path: baseline_path(name)?,
+                    benches: BTreeMap::new(),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn insert(&mut self, bench: &str, samples: &[f64]) {
+        self.benches.insert(bench.to_owned(), samples.to_vec());
+    }
+
+    /// Compares fresh samples of `bench` with the saved ones, if there are any.
+    pub fn compare(&self, bench: &str, samples: &[f64]) -> Option<Comparison> {
+        let old = match self.benches.get(bench) {
+            Some(old) if !old.is_empty() && !samples.is_empty() => old,
+            _ => return None,
+        };
+        let (old_median, new_median) = (old.median(), samples.median());
+        let change_pct = if old_median > 0.0 {
+            (new_median - old_median) / old_median * 100.0
+        } else {
+            0.0
+        };
+        let p_value = stats::mann_whitney_u_test(old, samples);
+        let verdict = if p_value >= SIGNIFICANCE || change_pct.abs() < NOISE_PCT {
+            Verdict::Unchanged
+        } else if change_pct > 0.0 {
+            Verdict::Regressed
+        } else {
+            Verdict::Improved
+        };
+        Some(Comparison {
+            change_pct: change_pct,
+            p_value: p_value,
+            verdict: verdict,
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let benches = self.benches.iter().map(|(name, samples)| {
+            (name.clone(), samples.to_json())
+        }).collect();
+        let mut out = File::create(&self.path)?;
+        write!(out, "{}", Json::Object(benches).pretty())
+    }
+}
+
+fn parse(contents: &str) -> Option<BTreeMap<String, Vec<f64>>> {
+    let json = match json::from_str(contents) {
+        Ok(Json::Object(obj)) => obj,
+        _ => return None,
+    };
+    let mut benches = BTreeMap::new();
+    for (name, samples) in json {
+        let samples = match samples {
+            Json::Array(samples) => samples.iter().map(|s| s.as_f64()).collect(),
+            _ => None,
+        };
+        match samples {
+            Some(samples) => benches.insert(name, samples),
+            None => return None,
+        };
+    }
+    Some(benches)
+}
+
+fn baseline_path(name: &str) -> io::Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let dir = match env::var_os("RUST_BENCH_BASELINE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => exe.parent().unwrap_or(Path::new(".")).join("bench-baselines"),
+    };
+    Ok(dir.join(name).join(format!("{}.json", binary_name(&exe))))
+}
+
+// The name of a test binary, minus the hash Cargo appends to it (as in
+// `collectionstests-6a4fe3b5f9d0c1e2`), which changes whenever the code does.
+fn binary_name(exe: &Path) -> String {
+    let stem = exe.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    match stem.rfind('-') {
+        Some(i) if stem.len() - i == 17 &&
+                   stem[i + 1..].chars().all(|c| c.is_digit(16)) => stem[..i].to_owned(),
+        _ => stem,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use super::{binary_name, parse, Baseline, Verdict};
+
+    #[test]
+    fn strips_cargo_hash() {
+        assert_eq!(binary_name(Path::new("/t/collectionstests-6a4fe3b5f9d0c1e2")),
+                   "collectionstests");
+        assert_eq!(binary_name(Path::new("/t/foo-bar.exe")), "foo-bar");
+    }
+
+    #[test]
+    fn compares_against_saved_samples() {
+        let mut baseline = parse("{}").unwrap();
+        baseline.insert("fast".to_string(), (100..150).map(|x| x as f64).collect());
+        let baseline = Baseline {
+            path: "unused".into(),
+            benches: baseline,
+        };
+
+        let same: Vec<f64> = (100..150).map(|x| x as f64).collect();
+        let slower: Vec<f64> = (150..200).map(|x| x as f64).collect();
+        let faster: Vec<f64> = (50..100).map(|x| x as f64).collect();
+        assert_eq!(baseline.compare("fast", &same).unwrap().verdict, Verdict::Unchanged);
+        assert_eq!(baseline.compare("fast", &slower).unwrap().verdict, Verdict::Regressed);
+        assert_eq!(baseline.compare("fast", &faster).unwrap().verdict, Verdict::Improved);
+        assert!(baseline.compare("missing", &same).is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_baselines() {
+        assert!(parse("[1, 2]").is_none());
+        assert!(parse("{\"a\": [\"x\"]}").is_none());
+        assert_eq!(parse("{\"a\": [1.5, 2]}").unwrap()["a"], vec![1.5, 2.0]);
+    }
+}
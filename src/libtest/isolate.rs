@@ -0,0 +1,192 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Running each test in a process of its own (`--isolate`).
+//!
+//! The harness re-executes its own binary with the same arguments, naming the
+//! test to run in an environment variable. The child runs just that test, with
+//! its output going straight to pipes read by the parent, and reports the
+//! outcome through its exit status. A test which segfaults, aborts or calls
+//! `process::exit` thus only takes its own process down.
+
+use std::env;
+use std::io::prelude::*;
+use std::io;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process::{self, Command, ExitStatus, Stdio};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use {TestDesc, TestDescAndFn, TestHandle, TestResult, MonitorMsg, ShouldPanic, FnBox};
+use {StaticTestFn, DynTestFn, StaticTestName, DynTestName, TrOk, TrFailed, TrFailedMsg};
+use {calc_result, convert_benchmarks_to_tests};
+
+/// Environment variable naming the test a child process should run.
+pub const CHILD_ENV: &'static str = "RUST_TEST_ISOLATED_CHILD";
+
+// Exit status of a child whose test failed without taking the process down
+const FAILED_STATUS: i32 = 101;
+
+/// Entry point of a child process: runs the single test named by `CHILD_ENV`
+/// and exits with a status telling the parent how it went.
+pub fn run_child(name: &str, tests: Vec<TestDescAndFn>) -> ! {
+    // Tests which spawn test binaries of their own shouldn't turn those into
+    // children as well.
+    env::remove_var(CHILD_ENV);
+
+    let test = convert_benchmarks_to_tests(tests).into_iter()
+                                                 .find(|t| t.desc.name.as_slice() == name);
+    let TestDescAndFn { desc, testfn } = match test {
+        Some(test) => test,
+        None => {
+            let _ = writeln!(io::stderr(), "error: no test named `{}`", name);
+            process::exit(FAILED_STATUS);
+        }
+    };
+    let testfn: Box<FnBox<()>> = match testfn {
+        StaticTestFn(f) => Box::new(move |()| f()),
+        DynTestFn(f) => f,
+        _ => {
+            let _ = writeln!(io::stderr(), "error: `{}` cannot be run in isolation", name);
+            process::exit(FAILED_STATUS);
+        }
+    };
+
+    let result = catch_unwind(AssertUnwindSafe(|| testfn.call_box(())));
+    match calc_result(&desc, result) {
+        TrOk => process::exit(0),
+        TrFailedMsg(msg) => {
+            let _ = writeln!(io::stderr(), "note: {}", msg);
+        }
+        _ => {}
+    }
+    process::exit(FAILED_STATUS)
+}
+
+/// Runs `desc` in a child process, reporting the result on `monitor_ch` once
+/// the child exits. The child is killed if `handle` gets cancelled.
+pub fn run_test_isolated(desc: TestDesc,
+                         monitor_ch: Sender<MonitorMsg>,
+                         nocapture: bool,
+                         handle: TestHandle) {
+    let cfg = thread::Builder::new().name(match desc.name {
+        DynTestName(ref name) => name.clone(),
+        StaticTestName(name) => name.to_owned(),
+    });
+    cfg.spawn(move || {
+        let start = Instant::now();
+        let result = match run_child_process(&desc, nocapture, &handle) {
+            Ok(Some(status)) => calc_child_result(&desc, status, &handle.output.lock().unwrap()),
+            // The harness gave up on this test and has already reported it
+            Ok(None) => return,
+            Err(e) => TrFailedMsg(format!("failed to run test in a new process: {}", e)),
+        };
+        let exec_time = start.elapsed();
+        let stdout = handle.output.lock().unwrap().clone();
+        monitor_ch.send((desc, result, stdout, exec_time)).unwrap();
+    }).unwrap();
+}
+
+fn run_child_process(desc: &TestDesc, nocapture: bool, handle: &TestHandle)
+                     -> io::Result<Option<ExitStatus>> {
+    let mut cmd = Command::new(env::current_exe()?);
+    cmd.args(env::args_os().skip(1))
+       .env(CHILD_ENV, desc.name.as_slice())
+       .stdin(Stdio::null());
+    if !nocapture {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = cmd.spawn()?;
+
+    // Both pipes need to be drained while the child runs, or it may block
+    // writing to one of them.
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(copy_into(stdout, handle.output.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(copy_into(stderr, handle.output.clone()));
+    }
+
+    let status;
+    loop {
+        if let Some(s) = child.try_wait()? {
+            status = Some(s);
+            break;
+        }
+        if handle.cancelled.load(Ordering::SeqCst) {
+            child.kill()?;
+            child.wait()?;
+            status = None;
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    for reader in readers {
+        let _ = reader.join();
+    }
+    Ok(status)
+}
+
+fn copy_into<R>(mut pipe: R, output: Arc<Mutex<Vec<u8>>>) -> thread::JoinHandle<()>
+    where R: Read + Send + 'static
+{
+    thread::spawn(move || {
+        let mut buf = [0; 4096];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => output.lock().unwrap().extend_from_slice(&buf[..n]),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    })
+}
+
+fn calc_child_result(desc: &TestDesc, status: ExitStatus, output: &[u8]) -> TestResult {
+    if status.success() {
+        return TrOk;
+    }
+    if status.code() == Some(FAILED_STATUS) {
+        return TrFailed;
+    }
+    // With `-C panic=abort` a panicking test takes its process down, which is
+    // exactly what a `#[should_panic]` test is expected to do.
+    if aborted(&status) {
+        match desc.should_panic {
+            ShouldPanic::No => {}
+            ShouldPanic::Yes => return TrOk,
+            ShouldPanic::YesWithMessage(msg) => {
+                return if String::from_utf8_lossy(output).contains(msg) {
+                    TrOk
+                } else {
+                    TrFailedMsg(format!("Panic did not include expected string '{}'", msg))
+                };
+            }
+        }
+    }
+    TrFailedMsg(format!("test process exited abnormally ({})", status))
+}
+
+#[cfg(unix)]
+fn aborted(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal() == Some(::libc::SIGABRT)
+}
+
+#[cfg(not(unix))]
+fn aborted(_status: &ExitStatus) -> bool {
+    false
+}
@@ -0,0 +1,218 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writer for JUnit-compatible XML reports of a test run.
+//!
+//! The report is accumulated while the tests run and written out in one go
+//! once the run finishes, since the `<testsuite>` element carries totals.
+
+use std::env;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use {TestDesc, TestResult, TrOk, TrFailed, TrFailedMsg, TrIgnored, TrTimedOut, TrMetrics,
+     TrBench};
+use fmt_bench_samples;
+
+enum Outcome {
+    Passed,
+    Failed(Option<String>),
+    Skipped,
+}
+
+struct TestCase {
+    name: String,
+    outcome: Outcome,
+    output: String,
+    time: Duration,
+}
+
+pub struct JUnitReport {
+    path: PathBuf,
+    cases: Vec<TestCase>,
+}
+
+impl JUnitReport {
+    pub fn new(path: PathBuf) -> JUnitReport {
+        JUnitReport {
+            path: path,
+            cases: Vec::new(),
+        }
+    }
+
+    pub fn add_result(&mut self,
+                      desc: &TestDesc,
+                      result: &TestResult,
+                      stdout: &[u8],
+                      time: Duration) {
+        let mut output = String::from_utf8_lossy(stdout).into_owned();
+        let outcome = match *result {
+            TrOk => Outcome::Passed,
+            TrFailed => Outcome::Failed(None),
+            TrFailedMsg(ref msg) => Outcome::Failed(Some(msg.clone())),
+            TrIgnored => Outcome::Skipped,
+            TrTimedOut => {
+                let msg = format!("test did not finish within {} seconds", time.as_secs());
+                Outcome::Failed(Some(msg))
+            }
+            TrMetrics(ref mm) => {
+                output.push_str(&mm.fmt_metrics());
+                Outcome::Passed
+            }
+            TrBench(ref bs) => {
+                output.push_str(&fmt_bench_samples(bs));
+                Outcome::Passed
+            }
+        };
+        self.cases.push(TestCase {
+            name: desc.name.to_string(),
+            outcome: outcome,
+            output: output,
+            time: time,
+        });
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        let mut out = File::create(&self.path)?;
+        out.write_all(self.to_xml(&suite_name()).as_bytes())
+    }
+
+    fn to_xml(&self, suite: &str) -> String {
+        let failures = self.cases.iter().filter(|c| match c.outcome {
+            Outcome::Failed(_) => true,
+            _ => false,
+        }).count();
+        let skipped = self.cases.iter().filter(|c| match c.outcome {
+            Outcome::Skipped => true,
+            _ => false,
+        }).count();
+        let total = self.cases.iter().fold(Duration::new(0, 0), |acc, c| acc + c.time);
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<testsuites>\n");
+        xml.push_str(&format!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" \
+                               errors=\"0\" skipped=\"{}\" time=\"{}\">\n",
+                              escape(suite),
+                              self.cases.len(),
+                              failures,
+                              skipped,
+                              fmt_secs(total)));
+        for case in &self.cases {
+            // JUnit consumers group tests by class, which maps well onto the
+            // module path of a test.
+            let (classname, name) = match case.name.rfind("::") {
+                Some(i) => (format!("{}::{}", suite, &case.name[..i]), &case.name[i + 2..]),
+                None => (suite.to_owned(), &case.name[..]),
+            };
+            xml.push_str(&format!("    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                                  escape(&classname),
+                                  escape(name),
+                                  fmt_secs(case.time)));
+            match case.outcome {
+                Outcome::Passed if case.output.is_empty() => {
+                    xml.push_str("/>\n");
+                    continue;
+                }
+                Outcome::Passed => xml.push_str(">\n"),
+                Outcome::Failed(ref msg) => {
+                    xml.push_str(">\n");
+                    let msg = msg.as_ref().map(|s| &**s).unwrap_or("test failed");
+                    xml.push_str(&format!("      <failure message=\"{}\"/>\n", escape(msg)));
+                }
+                Outcome::Skipped => xml.push_str(">\n      <skipped/>\n"),
+            }
+            if !case.output.is_empty() {
+                xml.push_str(&format!("      <system-out>{}</system-out>\n",
+                                      escape(&case.output)));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+// Names the suite after the test binary, e.g. `collectionstests`
+fn suite_name() -> String {
+    env::args().next()
+               .as_ref()
+               .and_then(|arg0| Path::new(arg0).file_stem())
+               .map(|stem| stem.to_string_lossy().into_owned())
+               .unwrap_or_else(|| "test".to_owned())
+}
+
+fn fmt_secs(dur: Duration) -> String {
+    format!("{}.{:03}", dur.as_secs(), dur.subsec_nanos() / 1_000_000)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 cannot represent most control characters, even escaped
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{JUnitReport, escape};
+    use {TestDesc, StaticTestName, ShouldPanic, TrOk, TrFailedMsg, TrIgnored};
+
+    fn desc(name: &'static str) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(escape("<a href=\"x\">&'"), "&lt;a href=&quot;x&quot;&gt;&amp;&apos;");
+        assert_eq!(escape("bell\x07"), "bell\\u{7}");
+    }
+
+    #[test]
+    fn reports_each_outcome() {
+        let mut report = JUnitReport::new("unused.xml".into());
+        report.add_result(&desc("a::pass"), &TrOk, b"", Duration::from_millis(1500));
+        report.add_result(&desc("a::fail"),
+                          &TrFailedMsg("bad <thing>".to_string()),
+                          b"printed",
+                          Duration::new(0, 0));
+        report.add_result(&desc("skip"), &TrIgnored, b"", Duration::new(0, 0));
+        let xml = report.to_xml("suite");
+
+        assert!(xml.contains("tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\" \
+                              time=\"1.500\""));
+        assert!(xml.contains("<testcase classname=\"suite::a\" name=\"pass\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure message=\"bad &lt;thing&gt;\"/>"));
+        assert!(xml.contains("<system-out>printed</system-out>"));
+        assert!(xml.contains("<testcase classname=\"suite\" name=\"skip\" time=\"0.000\">\n      \
+                              <skipped/>"));
+    }
+}
@@ -39,8 +39,10 @@
 #![feature(set_stdio)]
 #![feature(staged_api)]
 #![feature(panic_unwind)]
+#![feature(process_try_wait)]
 
 extern crate getopts;
+extern crate serialize;
 extern crate term;
 extern crate libc;
 extern crate panic_unwind;
@@ -53,6 +55,7 @@ use self::TestEvent::*;
 use self::NamePadding::*;
 use self::OutputLocation::*;
 
+use serialize::json::{self, Json, ToJson};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::any::Any;
 use std::cmp;
@@ -63,23 +66,28 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io;
 use std::iter::repeat;
+use std::mem;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Instant, Duration};
+use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 
 const TEST_WARN_TIMEOUT_S: u64 = 60;
 
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc, TestDescAndFn, TestOpts, TrFailed,
-             TrFailedMsg, TrIgnored, TrOk, Metric, MetricMap, StaticTestFn, StaticTestName,
-             DynTestName, DynTestFn, run_test, test_main, test_main_static, filter_tests,
-             parse_opts, StaticBenchFn, ShouldPanic};
+             TrFailedMsg, TrIgnored, TrTimedOut, TrOk, Metric, MetricMap, StaticTestFn,
+             StaticTestName, DynTestName, DynTestFn, run_test, test_main, test_main_static,
+             test_main_cases, TestCase, filter_tests, parse_opts, StaticBenchFn, ShouldPanic};
 }
 
 pub mod stats;
+mod baseline;
+mod isolate;
+mod junit;
 
 // The name of a test. By convention this follows the rules for rust
 // paths; i.e. it should be a series of identifiers separated by double
@@ -189,6 +197,7 @@ impl fmt::Debug for TestFn {
 pub struct Bencher {
     mode: BenchMode,
     summary: Option<stats::Summary>,
+    samples: Vec<f64>,
     pub bytes: u64,
 }
 
@@ -212,6 +221,9 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub should_panic: ShouldPanic,
+    /// Number of seconds after which the test is reported as timed out,
+    /// overriding `--test-timeout`. Set by `#[timeout = "N"]`.
+    pub timeout: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -255,6 +267,9 @@ impl Clone for MetricMap {
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>) {
+    if let Ok(name) = env::var(isolate::CHILD_ENV) {
+        isolate::run_child(&name, tests);
+    }
     let opts = match parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => panic!("{:?}", msg),
@@ -282,28 +297,52 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>) {
 // rather than a &[].
 pub fn test_main_static(tests: &[TestDescAndFn]) {
     let args = env::args().collect::<Vec<_>>();
-    let owned_tests = tests.iter()
-                           .map(|t| {
-                               match t.testfn {
-                                   StaticTestFn(f) => {
-                                       TestDescAndFn {
-                                           testfn: StaticTestFn(f),
-                                           desc: t.desc.clone(),
-                                       }
-                                   }
-                                   StaticBenchFn(f) => {
-                                       TestDescAndFn {
-                                           testfn: StaticBenchFn(f),
-                                           desc: t.desc.clone(),
-                                       }
-                                   }
-                                   _ => panic!("non-static tests passed to test::test_main_static"),
-                               }
-                           })
-                           .collect();
+    let owned_tests = tests.iter().map(make_owned_test).collect();
     test_main(&args, owned_tests)
 }
 
+fn make_owned_test(test: &TestDescAndFn) -> TestDescAndFn {
+    match test.testfn {
+        StaticTestFn(f) => {
+            TestDescAndFn {
+                testfn: StaticTestFn(f),
+                desc: test.desc.clone(),
+            }
+        }
+        StaticBenchFn(f) => {
+            TestDescAndFn {
+                testfn: StaticBenchFn(f),
+                desc: test.desc.clone(),
+            }
+        }
+        _ => panic!("non-static tests passed to test::test_main_static"),
+    }
+}
+
+/// A test which libtest knows how to run.
+///
+/// A crate compiled with `--test` and `#![test_runner = "test::test_main_cases"]`
+/// may mark statics and constants of any type implementing this trait with
+/// `#[test_case]`, and have them run alongside its `#[test]` and `#[bench]`
+/// functions, with all the usual filtering and reporting.
+pub trait TestCase {
+    /// Describes the test and provides the function running it.
+    fn to_test(&self) -> TestDescAndFn;
+}
+
+impl TestCase for TestDescAndFn {
+    fn to_test(&self) -> TestDescAndFn {
+        make_owned_test(self)
+    }
+}
+
+/// The entry point for `#![test_runner = "test::test_main_cases"]`, running
+/// the given test cases as `test_main` would.
+pub fn test_main_cases(cases: &[&TestCase]) {
+    let args = env::args().collect::<Vec<_>>();
+    test_main(&args, cases.iter().map(|case| case.to_test()).collect())
+}
+
 #[derive(Copy, Clone)]
 pub enum ColorConfig {
     AutoColor,
@@ -311,6 +350,15 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+/// How the console test runner reports events.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    /// Human-readable output, optionally condensed with `--quiet`.
+    Pretty,
+    /// One JSON object per line for every event, meant for other programs.
+    Json,
+}
+
 pub struct TestOpts {
     pub list: bool,
     pub filter: Option<String>,
@@ -319,11 +367,23 @@ pub struct TestOpts {
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<PathBuf>,
+    pub junit: Option<PathBuf>,
     pub nocapture: bool,
+    pub isolate: bool,
     pub color: ColorConfig,
+    pub format: OutputFormat,
     pub quiet: bool,
     pub test_threads: Option<usize>,
+    pub test_timeout: Option<u64>,
     pub skip: Vec<String>,
+    pub shuffle_seed: Option<u64>,
+    pub shard: Option<(usize, usize)>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub report_time: bool,
+    pub slowest: usize,
+    pub warn_time: Option<Duration>,
+    pub fail_time: Option<Duration>,
 }
 
 impl TestOpts {
@@ -337,11 +397,23 @@ impl TestOpts {
             run_tests: false,
             bench_benchmarks: false,
             logfile: None,
+            junit: None,
             nocapture: false,
+            isolate: false,
             color: AutoColor,
+            format: OutputFormat::Pretty,
             quiet: false,
             test_threads: None,
+            test_timeout: None,
             skip: vec![],
+            shuffle_seed: None,
+            shard: None,
+            save_baseline: None,
+            baseline: None,
+            report_time: false,
+            slowest: 0,
+            warn_time: None,
+            fail_time: None,
         }
     }
 }
@@ -358,18 +430,47 @@ fn optgroups() -> Vec<getopts::OptGroup> {
       getopts::optflag("h", "help", "Display this message (longer with --help)"),
       getopts::optopt("", "logfile", "Write logs to the specified file instead \
                           of stdout", "PATH"),
+      getopts::optopt("", "junit", "Write a JUnit XML report of the test run to the \
+                                    specified file", "PATH"),
       getopts::optflag("", "nocapture", "don't capture stdout/stderr of each \
                                          task, allow printing directly"),
+      getopts::optflag("", "isolate", "Run each test in a process of its own, so that a test \
+                                       which crashes or aborts only fails itself"),
       getopts::optopt("", "test-threads", "Number of threads used for running tests \
                                            in parallel", "n_threads"),
+      getopts::optopt("", "test-timeout", "Report tests running for longer than this many \
+                                           seconds as timed out and carry on with the rest \
+                                           of the suite", "SECONDS"),
       getopts::optmulti("", "skip", "Skip tests whose names contain FILTER (this flag can \
                                      be used multiple times)","FILTER"),
+      getopts::optflagopt("", "shuffle", "Run tests in a random order, printing the seed \
+                                          used. Pass it as --shuffle=SEED to reproduce an order",
+                          "SEED"),
+      getopts::optopt("", "shard-index", "Only run the tests of the given shard, counting \
+                                          from 0 (requires --shard-count)", "N"),
+      getopts::optopt("", "shard-count", "Split the tests into this many shards", "M"),
+      getopts::optopt("", "save-baseline", "Save the samples of each benchmark under the \
+                                            given name, for later use with --baseline", "NAME"),
+      getopts::optopt("", "baseline", "Compare each benchmark with the samples saved by \
+                                       --save-baseline, failing on significant regressions",
+                      "NAME"),
+      getopts::optflag("", "report-time", "Show how long each test took, and list the \
+                                           slowest tests at the end"),
+      getopts::optopt("", "slowest", "Number of slowest tests --report-time lists (10 by \
+                                      default)", "N"),
+      getopts::optopt("", "warn-time", "Highlight tests taking longer than this many seconds \
+                                        (implies --report-time)", "SECONDS"),
+      getopts::optopt("", "fail-time", "Fail tests taking longer than this many seconds",
+                      "SECONDS"),
       getopts::optflag("q", "quiet", "Display one character per test instead of one line"),
       getopts::optflag("", "exact", "Exactly match filters rather than by substring"),
       getopts::optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
-            never  = never colorize output;", "auto|always|never")]
+            never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure formatting of output:
+            pretty = print verbose output (default);
+            json   = print one JSON object per event;", "pretty|json")]
 }
 
 fn usage(binary: &str) {
@@ -400,14 +501,21 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[timeout = "N"] - This test (also labeled with #[test]) is reported as
+                     timed out if it runs for more than N seconds, overriding
+                     --test-timeout."#,
              usage = getopts::usage(&message, &optgroups()));
 }
 
 // Parses command line arguments into test options
 pub fn parse_opts(args: &[String]) -> Option<OptRes> {
-    let args_ = &args[1..];
-    let matches = match getopts::getopts(args_, &optgroups()) {
+    // A seed is only taken as `--shuffle=SEED`, so that a filter following a
+    // bare `--shuffle` isn't mistaken for one.
+    let args_: Vec<String> = args[1..].iter().map(|arg| {
+        if arg == "--shuffle" { "--shuffle=".to_string() } else { arg.clone() }
+    }).collect();
+    let matches = match getopts::getopts(&args_, &optgroups()) {
         Ok(m) => m,
         Err(f) => return Some(Err(f.to_string())),
     };
@@ -431,6 +539,8 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
     let logfile = matches.opt_str("logfile");
     let logfile = logfile.map(|s| PathBuf::from(&s));
 
+    let junit = matches.opt_str("junit").map(|s| PathBuf::from(&s));
+
     let bench_benchmarks = matches.opt_present("bench");
     let run_tests = !bench_benchmarks || matches.opt_present("test");
 
@@ -456,6 +566,66 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             None,
     };
 
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs) =>
+            match secs.parse::<u64>() {
+                Ok(0) =>
+                    return Some(Err(format!("argument for --test-timeout must not be 0"))),
+                Ok(n) => Some(n),
+                Err(e) =>
+                    return Some(Err(format!("argument for --test-timeout must be a number > 0 \
+                                             (error: {})", e)))
+            },
+        None =>
+            None,
+    };
+
+    let shuffle_seed = match matches.opt_str("shuffle") {
+        Some(ref seed) if seed.is_empty() => Some(random_seed()),
+        Some(seed) => match seed.parse::<u64>() {
+            Ok(seed) => Some(seed),
+            Err(e) => return Some(Err(format!("argument for --shuffle must be a number \
+                                               (error: {})", e))),
+        },
+        None => None,
+    };
+
+    let shard = match (matches.opt_str("shard-index"), matches.opt_str("shard-count")) {
+        (None, None) => None,
+        (Some(index), Some(count)) => {
+            match (index.parse::<usize>(), count.parse::<usize>()) {
+                (Ok(_), Ok(0)) =>
+                    return Some(Err(format!("argument for --shard-count must not be 0"))),
+                (Ok(index), Ok(count)) if index >= count =>
+                    return Some(Err(format!("argument for --shard-index must be less than \
+                                             --shard-count ({} >= {})", index, count))),
+                (Ok(index), Ok(count)) => Some((index, count)),
+                (Err(e), _) | (_, Err(e)) =>
+                    return Some(Err(format!("arguments for --shard-index and --shard-count \
+                                             must be numbers (error: {})", e))),
+            }
+        }
+        _ => return Some(Err(format!("--shard-index and --shard-count must be used together"))),
+    };
+
+    let warn_time = match parse_duration_opt(&matches, "warn-time") {
+        Ok(time) => time,
+        Err(e) => return Some(Err(e)),
+    };
+    let fail_time = match parse_duration_opt(&matches, "fail-time") {
+        Ok(time) => time,
+        Err(e) => return Some(Err(e)),
+    };
+    let report_time = matches.opt_present("report-time") || warn_time.is_some();
+    let slowest = match matches.opt_str("slowest") {
+        Some(n) => match n.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => return Some(Err(format!("argument for --slowest must be a number \
+                                               (error: {})", e))),
+        },
+        None => 10,
+    };
+
     let color = match matches.opt_str("color").as_ref().map(|s| &**s) {
         Some("auto") | None => AutoColor,
         Some("always") => AlwaysColor,
@@ -468,6 +638,17 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         }
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| &**s) {
+        Some("pretty") | None => OutputFormat::Pretty,
+        Some("json") => OutputFormat::Json,
+
+        Some(v) => {
+            return Some(Err(format!("argument for --format must be pretty or json (was \
+                                     {})",
+                                    v)))
+        }
+    };
+
     let test_opts = TestOpts {
         list: list,
         filter: filter,
@@ -476,19 +657,65 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         run_tests: run_tests,
         bench_benchmarks: bench_benchmarks,
         logfile: logfile,
+        junit: junit,
         nocapture: nocapture,
+        isolate: matches.opt_present("isolate"),
         color: color,
+        format: format,
         quiet: quiet,
         test_threads: test_threads,
+        test_timeout: test_timeout,
         skip: matches.opt_strs("skip"),
+        shuffle_seed: shuffle_seed,
+        shard: shard,
+        save_baseline: matches.opt_str("save-baseline"),
+        baseline: matches.opt_str("baseline"),
+        report_time: report_time,
+        slowest: slowest,
+        warn_time: warn_time,
+        fail_time: fail_time,
     };
 
     Some(Ok(test_opts))
 }
 
+// Parses an option giving a (possibly fractional) number of seconds
+fn parse_duration_opt(matches: &getopts::Matches, name: &str) -> Result<Option<Duration>, String> {
+    let secs = match matches.opt_str(name) {
+        Some(secs) => secs,
+        None => return Ok(None),
+    };
+    match secs.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs < u64::max_value() as f64 => {
+            let whole = secs.trunc();
+            Ok(Some(Duration::new(whole as u64, ((secs - whole) * 1e9) as u32)))
+        }
+        Ok(_) => Err(format!("argument for --{} must be a positive number of seconds", name)),
+        Err(e) => Err(format!("argument for --{} must be a number (error: {})", name, e)),
+    }
+}
+
+// State shared between the harness and a running test: the output the test
+// has produced so far, and whether the harness has given up waiting for it.
+#[derive(Clone)]
+struct TestHandle {
+    output: Arc<Mutex<Vec<u8>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TestHandle {
+    fn new() -> TestHandle {
+        TestHandle {
+            output: Arc::new(Mutex::new(Vec::new())),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
+    ns_iter_samples: Vec<f64>,
     mb_s: usize,
 }
 
@@ -498,6 +725,7 @@ pub enum TestResult {
     TrFailed,
     TrFailedMsg(String),
     TrIgnored,
+    TrTimedOut,
     TrMetrics(MetricMap),
     TrBench(BenchSamples),
 }
@@ -511,14 +739,25 @@ enum OutputLocation<T> {
 
 struct ConsoleTestState<T> {
     log_out: Option<File>,
+    junit: Option<junit::JUnitReport>,
     out: OutputLocation<T>,
     use_color: bool,
+    format: OutputFormat,
     quiet: bool,
+    shuffle_seed: Option<u64>,
+    baseline: Option<baseline::Baseline>,
+    save_baseline: Option<baseline::Baseline>,
     total: usize,
     passed: usize,
     failed: usize,
     ignored: usize,
     measured: usize,
+    regressed: usize,
+    report_time: bool,
+    slowest: usize,
+    warn_time: Option<Duration>,
+    fail_time: Option<Duration>,
+    exec_times: Vec<(TestName, Duration)>,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8>)>,
     max_name_len: usize, // number of columns to fill when aligning names
@@ -530,6 +769,15 @@ impl<T: Write> ConsoleTestState<T> {
             Some(ref path) => Some(File::create(path)?),
             None => None,
         };
+        let junit = opts.junit.as_ref().map(|path| junit::JUnitReport::new(path.clone()));
+        let baseline = match opts.baseline {
+            Some(ref name) => Some(baseline::Baseline::load(name)?),
+            None => None,
+        };
+        let save_baseline = match opts.save_baseline {
+            Some(ref name) => Some(baseline::Baseline::load_or_new(name)?),
+            None => None,
+        };
         let out = match term::stdout() {
             None => Raw(io::stdout()),
             Some(t) => Pretty(t),
@@ -538,13 +786,24 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(ConsoleTestState {
             out: out,
             log_out: log_out,
+            junit: junit,
             use_color: use_color(opts),
+            format: opts.format,
             quiet: opts.quiet,
+            shuffle_seed: opts.shuffle_seed,
+            baseline: baseline,
+            save_baseline: save_baseline,
             total: 0,
             passed: 0,
             failed: 0,
             ignored: 0,
             measured: 0,
+            regressed: 0,
+            report_time: opts.report_time,
+            slowest: opts.slowest,
+            warn_time: opts.warn_time,
+            fail_time: opts.fail_time,
+            exec_times: Vec::new(),
             metrics: MetricMap::new(),
             failures: Vec::new(),
             max_name_len: 0,
@@ -563,6 +822,10 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_short_result("ignored", "i", term::color::YELLOW)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("TIMEOUT", "T", term::color::RED)
+    }
+
     pub fn write_metric(&mut self) -> io::Result<()> {
         self.write_pretty("metric", term::color::CYAN)
     }
@@ -573,11 +836,16 @@ impl<T: Write> ConsoleTestState<T> {
 
     pub fn write_short_result(&mut self, verbose: &str, quiet: &str, color: term::color::Color)
                               -> io::Result<()> {
-        if self.quiet {
-            self.write_pretty(quiet, color)
-        } else {
-            self.write_pretty(verbose, color)?;
-            self.write_plain("\n")
+        let word = if self.quiet { quiet } else { verbose };
+        self.write_pretty(word, color)
+    }
+
+    pub fn write_exec_time(&mut self, exec_time: Duration) -> io::Result<()> {
+        let time = format!("<{}>", fmt_duration(exec_time));
+        self.write_plain(" ")?;
+        match self.warn_time {
+            Some(limit) if exec_time > limit => self.write_pretty(&time, term::color::YELLOW),
+            _ => self.write_plain(&time),
         }
     }
 
@@ -614,18 +882,40 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    pub fn write_json_event(&mut self, event: json::Object) -> io::Result<()> {
+        self.write_plain(&format!("{}\n", Json::Object(event)))
+    }
+
     pub fn write_run_start(&mut self, len: usize) -> io::Result<()> {
         self.total = len;
+        if self.format == OutputFormat::Json {
+            let mut event = json_event("suite", "started");
+            event.insert("test_count".to_string(), len.to_json());
+            if let Some(seed) = self.shuffle_seed {
+                event.insert("shuffle_seed".to_string(), seed.to_json());
+            }
+            return self.write_json_event(event);
+        }
         let noun = if len != 1 {
             "tests"
         } else {
             "test"
         };
-        self.write_plain(&format!("\nrunning {} {}\n", len, noun))
+        self.write_plain(&format!("\nrunning {} {}\n", len, noun))?;
+        match self.shuffle_seed {
+            Some(seed) => self.write_plain(&format!("shuffling with seed {} (rerun with \
+                                                     --shuffle={} for the same order)\n",
+                                                    seed, seed)),
+            None => Ok(()),
+        }
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc, align: NamePadding) -> io::Result<()> {
-        if self.quiet && align != PadOnRight {
+        if self.format == OutputFormat::Json {
+            let mut event = json_event("test", "started");
+            event.insert("name".to_string(), test.name.as_slice().to_json());
+            self.write_json_event(event)
+        } else if self.quiet && align != PadOnRight {
             Ok(())
         } else {
             let name = test.padded_name(self.max_name_len, align);
@@ -633,23 +923,134 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
-    pub fn write_result(&mut self, result: &TestResult) -> io::Result<()> {
+    pub fn write_result(&mut self, result: &TestResult, exec_time: Duration) -> io::Result<()> {
         match *result {
-            TrOk => self.write_ok(),
-            TrFailed | TrFailedMsg(_) => self.write_failed(),
-            TrIgnored => self.write_ignored(),
+            TrOk => self.write_ok()?,
+            TrFailed | TrFailedMsg(_) => self.write_failed()?,
+            TrIgnored => self.write_ignored()?,
+            TrTimedOut => self.write_timed_out()?,
             TrMetrics(ref mm) => {
                 self.write_metric()?;
-                self.write_plain(&format!(": {}\n", mm.fmt_metrics()))
+                return self.write_plain(&format!(": {}\n", mm.fmt_metrics()));
             }
             TrBench(ref bs) => {
                 self.write_bench()?;
-                self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
+                return self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)));
             }
         }
+        if self.quiet {
+            return Ok(());
+        }
+        if self.report_time && *result != TrIgnored {
+            self.write_exec_time(exec_time)?;
+        }
+        self.write_plain("\n")
+    }
+
+    pub fn write_bench_change(&mut self, cmp: &baseline::Comparison) -> io::Result<()> {
+        self.write_plain(&format!("{:>21} {:+.2}% (p = {:.4})",
+                                  "change:", cmp.change_pct, cmp.p_value))?;
+        match cmp.verdict {
+            baseline::Verdict::Regressed => {
+                self.write_plain(" ")?;
+                self.write_pretty("regressed", term::color::RED)?;
+            }
+            baseline::Verdict::Improved => {
+                self.write_plain(" ")?;
+                self.write_pretty("improved", term::color::GREEN)?;
+            }
+            baseline::Verdict::Unchanged => {}
+        }
+        self.write_plain("\n")
+    }
+
+    pub fn write_json_result(&mut self,
+                             test: &TestDesc,
+                             result: &TestResult,
+                             stdout: &[u8],
+                             exec_time: Option<Duration>,
+                             cmp: Option<&baseline::Comparison>)
+                             -> io::Result<()> {
+        let mut event = match *result {
+            TrOk => json_event("test", "ok"),
+            TrFailed => json_event("test", "failed"),
+            TrFailedMsg(ref msg) => {
+                let mut event = json_event("test", "failed");
+                event.insert("message".to_string(), msg.to_json());
+                event
+            }
+            TrIgnored => json_event("test", "ignored"),
+            TrTimedOut => json_event("test", "timeout"),
+            TrMetrics(MetricMap(ref mm)) => {
+                let mut event = json_event("metric", "ok");
+                let metrics = mm.iter().map(|(k, v)| {
+                    let mut metric = json::Object::new();
+                    metric.insert("value".to_string(), v.value.to_json());
+                    metric.insert("noise".to_string(), v.noise.to_json());
+                    (k.clone(), Json::Object(metric))
+                }).collect();
+                event.insert("metrics".to_string(), Json::Object(metrics));
+                event
+            }
+            TrBench(ref bs) => {
+                let mut event = json_event("bench", "ok");
+                event.insert("median".to_string(), bs.ns_iter_summ.median.to_json());
+                event.insert("deviation".to_string(),
+                             (bs.ns_iter_summ.max - bs.ns_iter_summ.min).to_json());
+                event.insert("mb_s".to_string(), bs.mb_s.to_json());
+                if let Some(cmp) = cmp {
+                    let verdict = match cmp.verdict {
+                        baseline::Verdict::Regressed => "regressed",
+                        baseline::Verdict::Improved => "improved",
+                        baseline::Verdict::Unchanged => "unchanged",
+                    };
+                    event.insert("change".to_string(), cmp.change_pct.to_json());
+                    event.insert("p_value".to_string(), cmp.p_value.to_json());
+                    event.insert("verdict".to_string(), verdict.to_json());
+                }
+                event
+            }
+        };
+        event.insert("name".to_string(), test.name.as_slice().to_json());
+        if let Some(exec_time) = exec_time {
+            let secs = exec_time.as_secs() as f64 + exec_time.subsec_nanos() as f64 / 1e9;
+            event.insert("exec_time".to_string(), secs.to_json());
+        }
+        match *result {
+            TrFailed | TrFailedMsg(_) | TrTimedOut if !stdout.is_empty() => {
+                event.insert("stdout".to_string(),
+                             String::from_utf8_lossy(stdout).to_json());
+            }
+            _ => {}
+        }
+        self.write_json_event(event)
+    }
+
+    // Saves the samples of a benchmark if asked to, and compares them with the
+    // baseline, counting significant regressions as failures of the run.
+    fn record_bench(&mut self, test: &TestDesc, bs: &BenchSamples)
+                    -> Option<baseline::Comparison> {
+        let name = test.name.as_slice();
+        if let Some(ref mut baseline) = self.save_baseline {
+            baseline.insert(name, &bs.ns_iter_samples);
+        }
+        let cmp = match self.baseline {
+            Some(ref baseline) => baseline.compare(name, &bs.ns_iter_samples),
+            None => None,
+        };
+        if let Some(baseline::Comparison { verdict: baseline::Verdict::Regressed, .. }) = cmp {
+            self.regressed += 1;
+        }
+        cmp
     }
 
     pub fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            let mut event = json_event("test", "timeout_warning");
+            event.insert("name".to_string(), desc.name.as_slice().to_json());
+            event.insert("seconds".to_string(), TEST_WARN_TIMEOUT_S.to_json());
+            return self.write_json_event(event);
+        }
         self.write_plain(&format!("test {} has been running for over {} seconds\n",
                                   desc.name,
                                   TEST_WARN_TIMEOUT_S))
@@ -671,6 +1072,7 @@ impl<T: Write> ConsoleTestState<T> {
                         TrFailed => "failed".to_owned(),
                         TrFailedMsg(ref msg) => format!("failed: {}", msg),
                         TrIgnored => "ignored".to_owned(),
+                        TrTimedOut => "timeout".to_owned(),
                         TrMetrics(ref mm) => mm.fmt_metrics(),
                         TrBench(ref bs) => fmt_bench_samples(bs),
                     },
@@ -703,13 +1105,55 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(())
     }
 
+    pub fn write_slowest(&mut self) -> io::Result<()> {
+        let mut exec_times = mem::replace(&mut self.exec_times, Vec::new());
+        exec_times.sort_by(|&(ref a_name, a_time), &(ref b_name, b_time)| {
+            b_time.cmp(&a_time).then_with(|| a_name.as_slice().cmp(b_name.as_slice()))
+        });
+        exec_times.truncate(self.slowest);
+        self.write_plain("\nslowest tests:\n")?;
+        for &(ref name, exec_time) in &exec_times {
+            self.write_plain("    ")?;
+            let time = format!("{:>9}", fmt_duration(exec_time));
+            match self.warn_time {
+                Some(limit) if exec_time > limit => self.write_pretty(&time, term::color::YELLOW)?,
+                _ => self.write_plain(&time)?,
+            }
+            self.write_plain(&format!("  {}\n", name))?;
+        }
+        Ok(())
+    }
+
     pub fn write_run_finish(&mut self) -> io::Result<bool> {
         assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
 
-        let success = self.failed == 0;
+        if let Some(ref junit) = self.junit {
+            junit.write()?;
+        }
+        if let Some(ref baseline) = self.save_baseline {
+            baseline.save()?;
+        }
+
+        let success = self.failed == 0 && self.regressed == 0;
+        if self.format == OutputFormat::Json {
+            let mut event = json_event("suite", if success { "ok" } else { "failed" });
+            event.insert("passed".to_string(), self.passed.to_json());
+            event.insert("failed".to_string(), self.failed.to_json());
+            event.insert("ignored".to_string(), self.ignored.to_json());
+            event.insert("measured".to_string(), self.measured.to_json());
+            if self.baseline.is_some() {
+                event.insert("regressed".to_string(), self.regressed.to_json());
+            }
+            self.write_json_event(event)?;
+            return Ok(success);
+        }
+
         if !success {
             self.write_failures()?;
         }
+        if self.report_time && self.slowest > 0 && !self.exec_times.is_empty() {
+            self.write_slowest()?;
+        }
 
         self.write_plain("\ntest result: ")?;
         if success {
@@ -718,16 +1162,33 @@ impl<T: Write> ConsoleTestState<T> {
         } else {
             self.write_pretty("FAILED", term::color::RED)?;
         }
-        let s = format!(". {} passed; {} failed; {} ignored; {} measured\n\n",
+        let s = format!(". {} passed; {} failed; {} ignored; {} measured",
                         self.passed,
                         self.failed,
                         self.ignored,
                         self.measured);
         self.write_plain(&s)?;
+        if self.baseline.is_some() {
+            self.write_plain(&format!("; {} regressed", self.regressed))?;
+        }
+        self.write_plain("\n\n")?;
         return Ok(success);
     }
 }
 
+// Starts a JSON event object of the form `{ "type": kind, "event": event }`
+fn json_event(kind: &str, event: &str) -> json::Object {
+    let mut obj = json::Object::new();
+    obj.insert("type".to_string(), kind.to_json());
+    obj.insert("event".to_string(), event.to_json());
+    obj
+}
+
+// Formats a duration as seconds with millisecond precision, e.g. `1.250s`
+fn fmt_duration(dur: Duration) -> String {
+    format!("{}.{:03}s", dur.as_secs(), dur.subsec_nanos() / 1_000_000)
+}
+
 // Format a number with thousands separators
 fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     use std::fmt::Write;
@@ -820,9 +1281,42 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
             TeWait(ref test, padding) => st.write_test_start(test, padding),
             TeTimeout(ref test) => st.write_timeout(test),
-            TeResult(test, result, stdout) => {
+            TeResult(test, result, stdout, exec_time) => {
+                let result = match (result, st.fail_time) {
+                    (TrOk, Some(limit)) if exec_time > limit => {
+                        TrFailedMsg(format!("test took {}, longer than the --fail-time limit \
+                                             of {}",
+                                            fmt_duration(exec_time),
+                                            fmt_duration(limit)))
+                    }
+                    (result, _) => result,
+                };
+                if st.report_time {
+                    match result {
+                        TrIgnored | TrMetrics(_) | TrBench(_) => {}
+                        _ => st.exec_times.push((test.name.clone(), exec_time)),
+                    }
+                }
+                if let Some(ref mut junit) = st.junit {
+                    junit.add_result(&test, &result, &stdout, exec_time);
+                }
                 st.write_log_result(&test, &result)?;
-                st.write_result(&result)?;
+                let cmp = match result {
+                    TrBench(ref bs) => st.record_bench(&test, bs),
+                    _ => None,
+                };
+                match st.format {
+                    OutputFormat::Pretty => {
+                        st.write_result(&result, exec_time)?;
+                        if let Some(ref cmp) = cmp {
+                            st.write_bench_change(cmp)?;
+                        }
+                    }
+                    OutputFormat::Json => {
+                        let exec_time = if st.report_time { Some(exec_time) } else { None };
+                        st.write_json_result(&test, &result, &stdout, exec_time, cmp.as_ref())?
+                    }
+                }
                 match result {
                     TrOk => st.passed += 1,
                     TrIgnored => st.ignored += 1,
@@ -853,6 +1347,15 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         );
                         st.failures.push((test, stdout));
                     }
+                    TrTimedOut => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(
+                            format!("note: test did not finish within {} seconds",
+                                    exec_time.as_secs()).as_bytes()
+                        );
+                        st.failures.push((test, stdout));
+                    }
                 }
                 Ok(())
             }
@@ -874,34 +1377,55 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
     return st.write_run_finish();
 }
 
+// A console state writing into memory, set up from `opts` as
+// `ConsoleTestState::new` would, for the tests below.
+#[cfg(test)]
+fn test_state(opts: &TestOpts) -> ConsoleTestState<Vec<u8>> {
+    ConsoleTestState {
+        log_out: None,
+        junit: None,
+        out: Raw(Vec::new()),
+        use_color: false,
+        format: opts.format,
+        quiet: opts.quiet,
+        shuffle_seed: opts.shuffle_seed,
+        baseline: None,
+        save_baseline: None,
+        total: 0,
+        passed: 0,
+        failed: 0,
+        ignored: 0,
+        measured: 0,
+        regressed: 0,
+        report_time: opts.report_time,
+        slowest: opts.slowest,
+        warn_time: opts.warn_time,
+        fail_time: opts.fail_time,
+        exec_times: Vec::new(),
+        max_name_len: 10,
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+    }
+}
+
 #[test]
 fn should_sort_failures_before_printing_them() {
     let test_a = TestDesc {
         name: StaticTestName("a"),
         ignore: false,
         should_panic: ShouldPanic::No,
+        timeout: None,
     };
 
     let test_b = TestDesc {
         name: StaticTestName("b"),
         ignore: false,
         should_panic: ShouldPanic::No,
+        timeout: None,
     };
 
-    let mut st = ConsoleTestState {
-        log_out: None,
-        out: Raw(Vec::new()),
-        use_color: false,
-        quiet: false,
-        total: 0,
-        passed: 0,
-        failed: 0,
-        ignored: 0,
-        measured: 0,
-        max_name_len: 10,
-        metrics: MetricMap::new(),
-        failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
-    };
+    let mut st = test_state(&TestOpts::new());
+    st.failures = vec![(test_b, Vec::new()), (test_a, Vec::new())];
 
     st.write_failures().unwrap();
     let s = match st.out {
@@ -914,6 +1438,63 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn json_format_emits_one_object_per_result() {
+    let test = TestDesc {
+        name: StaticTestName("a"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
+    };
+
+    let mut st = test_state(&TestOpts {
+        format: OutputFormat::Json,
+        ..TestOpts::new()
+    });
+
+    st.write_json_result(&test, &TrFailedMsg("boom".to_string()), b"output", None, None)
+      .unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s.lines().count(), 1);
+    let event = json::from_str(&s).unwrap();
+    assert_eq!(event.find("type").and_then(|j| j.as_string()), Some("test"));
+    assert_eq!(event.find("event").and_then(|j| j.as_string()), Some("failed"));
+    assert_eq!(event.find("name").and_then(|j| j.as_string()), Some("a"));
+    assert_eq!(event.find("message").and_then(|j| j.as_string()), Some("boom"));
+    assert_eq!(event.find("stdout").and_then(|j| j.as_string()), Some("output"));
+}
+
+#[test]
+fn report_time_lists_slowest_tests() {
+    let mut st = test_state(&TestOpts {
+        report_time: true,
+        slowest: 2,
+        ..TestOpts::new()
+    });
+    st.exec_times = vec![(StaticTestName("fast"), Duration::from_millis(5)),
+                         (StaticTestName("slow"), Duration::from_millis(2500)),
+                         (StaticTestName("medium"), Duration::from_millis(300))];
+
+    st.write_result(&TrOk, Duration::from_millis(1250)).unwrap();
+    st.write_result(&TrIgnored, Duration::new(0, 0)).unwrap();
+    st.write_slowest().unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s, "ok <1.250s>\n\
+                   ignored\n\
+                   \n\
+                   slowest tests:\n    \
+                   \x20  2.500s  slow\n    \
+                   \x20  0.300s  medium\n");
+}
+
 fn use_color(opts: &TestOpts) -> bool {
     match opts.color {
         AutoColor => !opts.nocapture && stdout_isatty(),
@@ -953,17 +1534,17 @@ fn stdout_isatty() -> bool {
 pub enum TestEvent {
     TeFiltered(Vec<TestDesc>),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8>),
+    TeResult(TestDesc, TestResult, Vec<u8>, Duration),
     TeTimeout(TestDesc),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>);
+pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>, Duration);
 
 
 pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F) -> io::Result<()>
     where F: FnMut(TestEvent) -> io::Result<()>
 {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::sync::mpsc::RecvTimeoutError;
 
     let mut filtered_tests = filter_tests(opts, tests);
@@ -998,6 +1579,13 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
 
     let mut running_tests: HashMap<TestDesc, Instant> = HashMap::new();
 
+    // Tests with a time limit, along with their handles. A test past its
+    // deadline is reported right away. Test processes are killed, but a test
+    // thread is left behind, so whatever it sends later has to be dropped.
+    let mut deadlines: HashMap<TestDesc, Instant> = HashMap::new();
+    let mut handles: HashMap<TestDesc, TestHandle> = HashMap::new();
+    let mut abandoned: HashSet<TestDesc> = HashSet::new();
+
     fn get_timed_out_tests(running_tests: &mut HashMap<TestDesc, Instant>) -> Vec<TestDesc> {
         let now = Instant::now();
         let timed_out = running_tests.iter()
@@ -1030,18 +1618,42 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
             }
             let timeout = Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S);
             running_tests.insert(test.desc.clone(), timeout);
-            run_test(opts, !opts.run_tests, test, tx.clone());
+            let handle = TestHandle::new();
+            if let Some(limit) = time_limit(opts, &test.desc) {
+                deadlines.insert(test.desc.clone(), Instant::now() + limit);
+                handles.insert(test.desc.clone(), handle.clone());
+            }
+            run_test_captured(opts, !opts.run_tests, test, tx.clone(), handle);
             pending += 1;
         }
 
         let mut res;
         loop {
-            if let Some(timeout) = calc_timeout(&running_tests) {
+            let timeout = match (calc_timeout(&running_tests), calc_timeout(&deadlines)) {
+                (Some(warn), Some(kill)) => Some(cmp::min(warn, kill)),
+                (warn, kill) => warn.or(kill),
+            };
+            if let Some(timeout) = timeout {
                 res = rx.recv_timeout(timeout);
                 for test in get_timed_out_tests(&mut running_tests) {
                     callback(TeTimeout(test))?;
                 }
-                if res != Err(RecvTimeoutError::Timeout) {
+                let mut gave_up = false;
+                for test in get_timed_out_tests(&mut deadlines) {
+                    running_tests.remove(&test);
+                    let handle = handles.remove(&test).unwrap();
+                    handle.cancelled.store(true, Ordering::SeqCst);
+                    let stdout = handle.output.lock().unwrap().clone();
+                    let limit = time_limit(opts, &test).unwrap();
+                    if concurrency != 1 {
+                        callback(TeWait(test.clone(), PadNone))?;
+                    }
+                    callback(TeResult(test.clone(), TrTimedOut, stdout, limit))?;
+                    abandoned.insert(test);
+                    pending -= 1;
+                    gave_up = true;
+                }
+                if res != Err(RecvTimeoutError::Timeout) || gave_up {
                     break;
                 }
             } else {
@@ -1050,13 +1662,21 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
             }
         }
 
-        let (desc, result, stdout) = res.unwrap();
+        let (desc, result, stdout, exec_time) = match res {
+            Err(RecvTimeoutError::Timeout) => continue,
+            res => res.unwrap(),
+        };
+        if abandoned.remove(&desc) {
+            continue;
+        }
         running_tests.remove(&desc);
+        deadlines.remove(&desc);
+        handles.remove(&desc);
 
         if concurrency != 1 {
             callback(TeWait(desc.clone(), PadNone))?;
         }
-        callback(TeResult(desc, result, stdout))?;
+        callback(TeResult(desc, result, stdout, exec_time))?;
         pending -= 1;
     }
 
@@ -1066,13 +1686,18 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
         for b in filtered_benchs_and_metrics {
             callback(TeWait(b.desc.clone(), b.testfn.padding()))?;
             run_test(opts, false, b, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
+            let (test, result, stdout, exec_time) = rx.recv().unwrap();
+            callback(TeResult(test, result, stdout, exec_time))?;
         }
     }
     Ok(())
 }
 
+// The time a test may run for before it is reported as timed out, if any
+fn time_limit(opts: &TestOpts, desc: &TestDesc) -> Option<Duration> {
+    desc.timeout.or(opts.test_timeout).map(Duration::from_secs)
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -1243,9 +1868,45 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
+    // Only keep our shard. This happens before shuffling so that every shard
+    // agrees on which tests belong to it, whatever the seed.
+    if let Some((index, count)) = opts.shard {
+        filtered = filtered.into_iter()
+                           .enumerate()
+                           .filter(|&(i, _)| i % count == index)
+                           .map(|(_, test)| test)
+                           .collect();
+    }
+
+    if let Some(seed) = opts.shuffle_seed {
+        shuffle_tests(seed, &mut filtered);
+    }
+
     filtered
 }
 
+// Fisher-Yates shuffle driven by SplitMix64, which is simple enough to give
+// the same order for a seed on every platform and across releases.
+fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    };
+    for i in (1..tests.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        tests.swap(i, j);
+    }
+}
+
+fn random_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::new(0, 0));
+    now.as_secs() ^ ((now.subsec_nanos() as u64) << 32)
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests.into_iter().map(|x| {
@@ -1273,17 +1934,28 @@ pub fn run_test(opts: &TestOpts,
                 force_ignore: bool,
                 test: TestDescAndFn,
                 monitor_ch: Sender<MonitorMsg>) {
+    run_test_captured(opts, force_ignore, test, monitor_ch, TestHandle::new())
+}
+
+// Same as `run_test`, but lets the caller keep an eye on the test through
+// `handle` while it is running.
+fn run_test_captured(opts: &TestOpts,
+                     force_ignore: bool,
+                     test: TestDescAndFn,
+                     monitor_ch: Sender<MonitorMsg>,
+                     handle: TestHandle) {
 
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, Vec::new(), Duration::new(0, 0))).unwrap();
         return;
     }
 
     fn run_test_inner(desc: TestDesc,
                       monitor_ch: Sender<MonitorMsg>,
                       nocapture: bool,
+                      data: Arc<Mutex<Vec<u8>>>,
                       testfn: Box<FnBox<()>>) {
         struct Sink(Arc<Mutex<Vec<u8>>>);
         impl Write for Sink {
@@ -1296,7 +1968,6 @@ pub fn run_test(opts: &TestOpts,
         }
 
         // Buffer for capturing standard I/O
-        let data = Arc::new(Mutex::new(Vec::new()));
         let data2 = data.clone();
 
         let name = desc.name.clone();
@@ -1310,9 +1981,11 @@ pub fn run_test(opts: &TestOpts,
                 None
             };
 
+            let start = Instant::now();
             let result = catch_unwind(AssertUnwindSafe(|| {
                 testfn.call_box(())
             }));
+            let exec_time = start.elapsed();
 
             if let Some((printio, panicio)) = oldio {
                 io::set_print(printio);
@@ -1321,7 +1994,7 @@ pub fn run_test(opts: &TestOpts,
 
             let test_result = calc_result(&desc, result);
             let stdout = data.lock().unwrap().to_vec();
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            monitor_ch.send((desc.clone(), test_result, stdout, exec_time)).unwrap();
         };
 
 
@@ -1340,31 +2013,35 @@ pub fn run_test(opts: &TestOpts,
         }
     }
 
+    let start = Instant::now();
     match testfn {
         DynBenchFn(bencher) => {
             let bs = ::bench::benchmark(|harness| bencher.run(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
             let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         DynMetricFn(f) => {
             let mut mm = MetricMap::new();
             f.call_box(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         StaticMetricFn(f) => {
             let mut mm = MetricMap::new();
             f(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), start.elapsed())).unwrap();
             return;
         }
-        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
-        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture,
+        DynTestFn(_) | StaticTestFn(_) if opts.isolate => {
+            isolate::run_test_isolated(desc, monitor_ch, opts.nocapture, handle)
+        }
+        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, handle.output, f),
+        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, handle.output,
                                           Box::new(move |()| f())),
     }
 }
@@ -1457,7 +2134,9 @@ impl Bencher {
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        let (summary, samples) = iter_samples(&mut inner);
+        self.summary = Some(summary);
+        self.samples = samples;
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
@@ -1485,6 +2164,14 @@ fn ns_iter_inner<T, F>(inner: &mut F, k: u64) -> u64
 
 pub fn iter<T, F>(inner: &mut F) -> stats::Summary
     where F: FnMut() -> T
+{
+    iter_samples(inner).0
+}
+
+// Like `iter`, but also hands back the samples the summary was computed from,
+// as measured rather than winsorized
+fn iter_samples<T, F>(inner: &mut F) -> (stats::Summary, Vec<f64>)
+    where F: FnMut() -> T
 {
     // Initial bench run to get ballpark figure.
     let ns_single = ns_iter_inner(inner, 1);
@@ -1518,6 +2205,9 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             *p = ns as f64 / (5 * n) as f64;
         }
 
+        // The samples are kept as they were measured for baselines, and only
+        // the summary is taken from the winsorized ones.
+        let raw = samples.to_vec();
         stats::winsorize(samples, 5.0);
         let summ5 = stats::Summary::new(samples);
 
@@ -1527,13 +2217,13 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         // stable median.
         if loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0 &&
            summ.median - summ5.median < summ5.median_abs_dev {
-            return summ5;
+            return (summ5, raw);
         }
 
         total_run = total_run + loop_run;
         // Longest we ever run for is 3s.
         if total_run > Duration::from_secs(3) {
-            return summ5;
+            return (summ5, raw);
         }
 
         // If we overflow here just return the results so far. We check a
@@ -1543,7 +2233,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return (summ5, raw);
             }
         };
     }
@@ -1560,6 +2250,7 @@ pub mod bench {
         let mut bs = Bencher {
             mode: BenchMode::Auto,
             summary: None,
+            samples: Vec::new(),
             bytes: 0,
         };
 
@@ -1570,6 +2261,7 @@ pub mod bench {
 
                 BenchSamples {
                     ns_iter_summ: ns_iter_summ,
+                    ns_iter_samples: bs.samples,
                     mb_s: mb_s as usize,
                 }
             }
@@ -1579,6 +2271,7 @@ pub mod bench {
                 let samples: &mut [f64] = &mut [0.0_f64; 1];
                 BenchSamples {
                     ns_iter_summ: stats::Summary::new(samples),
+                    ns_iter_samples: Vec::new(),
                     mb_s: 0,
                 }
             }
@@ -1591,6 +2284,7 @@ pub mod bench {
         let mut bs = Bencher {
             mode: BenchMode::Single,
             summary: None,
+            samples: Vec::new(),
             bytes: 0,
         };
         bs.bench(f);
@@ -1599,12 +2293,17 @@ pub mod bench {
 
 #[cfg(test)]
 mod tests {
-    use test::{TrFailed, TrFailedMsg, TrIgnored, TrOk, filter_tests, parse_opts, TestDesc,
-               TestDescAndFn, TestOpts, run_test, MetricMap, StaticTestName, DynTestName,
-               DynTestFn, ShouldPanic};
+    use test::{TrFailed, TrFailedMsg, TrIgnored, TrTimedOut, TrOk, filter_tests, parse_opts,
+               TestDesc, TestDescAndFn, TestOpts, run_test, MetricMap, StaticTestName,
+               DynTestName, DynTestFn, StaticTestFn, ShouldPanic, TestCase};
     use std::sync::mpsc::channel;
     use bench;
     use Bencher;
+    use OutputFormat;
+    use TestEvent::TeResult;
+    use run_tests;
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     pub fn do_not_run_ignored_tests() {
@@ -1616,12 +2315,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1633,12 +2333,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1652,12 +2353,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1671,12 +2373,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("error message"),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1692,12 +2395,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage(expected),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailedMsg(format!("{} '{}'", failed_msg, expected)));
     }
 
@@ -1709,15 +2413,45 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move |()| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
+    #[test]
+    fn test_timeout_gives_up_on_hung_test() {
+        fn f() {
+            println!("partial output");
+            thread::sleep(Duration::from_secs(60));
+        }
+        let desc = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                timeout: Some(1),
+            },
+            testfn: DynTestFn(Box::new(move |()| f())),
+        };
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        let mut results = Vec::new();
+        run_tests(&opts, vec![desc], |event| {
+            if let TeResult(_, res, stdout, _) = event {
+                results.push((res, stdout));
+            }
+            Ok(())
+        }).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0 == TrTimedOut);
+        assert_eq!(&results[0].1[..], &b"partial output\n"[..]);
+    }
+
     #[test]
     fn parse_ignored_flag() {
         let args = vec!["progname".to_string(), "filter".to_string(), "--ignored".to_string()];
@@ -1728,6 +2462,19 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn parse_format_flag() {
+        let args = vec!["progname".to_string(), "--format".to_string(), "json".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_format_flag"),
+        };
+        assert_eq!(opts.format, OutputFormat::Json);
+
+        let args = vec!["progname".to_string(), "--format".to_string(), "xml".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1742,6 +2489,7 @@ mod tests {
                                  name: StaticTestName("1"),
                                  ignore: true,
                                  should_panic: ShouldPanic::No,
+                                 timeout: None,
                              },
                              testfn: DynTestFn(Box::new(move |()| {})),
                          },
@@ -1750,6 +2498,7 @@ mod tests {
                                  name: StaticTestName("2"),
                                  ignore: false,
                                  should_panic: ShouldPanic::No,
+                                 timeout: None,
                              },
                              testfn: DynTestFn(Box::new(move |()| {})),
                          }];
@@ -1773,6 +2522,7 @@ mod tests {
                     name: StaticTestName(name),
                     ignore: false,
                     should_panic: ShouldPanic::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move |()| {}))
             })
@@ -1831,6 +2581,122 @@ mod tests {
         assert_eq!(exact.len(), 1);
     }
 
+    #[test]
+    pub fn shard_and_shuffle_tests() {
+        fn tests() -> Vec<TestDescAndFn> {
+            (0..10).map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("test{}", i)),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move |()| {}))
+            })
+            .collect()
+        }
+        fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+            tests.into_iter().map(|t| t.desc.name.to_string()).collect()
+        }
+
+        // Every test ends up in exactly one shard
+        let mut sharded = Vec::new();
+        for index in 0..3 {
+            let shard = names(filter_tests(&TestOpts {
+                    shard: Some((index, 3)),
+                    ..TestOpts::new()
+                }, tests()));
+            assert!(shard.len() == 3 || shard.len() == 4);
+            sharded.extend(shard);
+        }
+        sharded.sort();
+        assert_eq!(sharded, names(filter_tests(&TestOpts::new(), tests())));
+
+        // The same seed gives the same permutation
+        let shuffled = names(filter_tests(&TestOpts {
+                shuffle_seed: Some(42),
+                ..TestOpts::new()
+            }, tests()));
+        let again = names(filter_tests(&TestOpts {
+                shuffle_seed: Some(42),
+                ..TestOpts::new()
+            }, tests()));
+        assert_eq!(shuffled, again);
+        assert!(shuffled != names(filter_tests(&TestOpts::new(), tests())));
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, names(filter_tests(&TestOpts::new(), tests())));
+    }
+
+    #[test]
+    fn parse_shard_flags() {
+        let args = vec!["progname".to_string(),
+                        "--shard-index".to_string(), "1".to_string(),
+                        "--shard-count".to_string(), "4".to_string(),
+                        "--shuffle=7".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shard_flags"),
+        };
+        assert_eq!(opts.shard, Some((1, 4)));
+        assert_eq!(opts.shuffle_seed, Some(7));
+
+        let args = vec!["progname".to_string(), "--shuffle".to_string(), "foo".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shard_flags"),
+        };
+        assert!(opts.shuffle_seed.is_some());
+        assert_eq!(opts.filter, Some("foo".to_string()));
+
+        let args = vec!["progname".to_string(),
+                        "--shard-index".to_string(), "4".to_string(),
+                        "--shard-count".to_string(), "4".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+
+        let args = vec!["progname".to_string(), "--shard-index".to_string(), "0".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_report_time_flags() {
+        let args = vec!["progname".to_string(),
+                        "--warn-time".to_string(), "0.5".to_string(),
+                        "--fail-time=2".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_report_time_flags"),
+        };
+        assert!(opts.report_time);
+        assert_eq!(opts.slowest, 10);
+        assert_eq!(opts.warn_time, Some(Duration::from_millis(500)));
+        assert_eq!(opts.fail_time, Some(Duration::from_secs(2)));
+
+        let args = vec!["progname".to_string(), "--fail-time=-1".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn static_tests_are_test_cases() {
+        fn f() {}
+        let test = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("f"),
+                ignore: true,
+                should_panic: ShouldPanic::No,
+                timeout: Some(5),
+            },
+            testfn: StaticTestFn(f),
+        };
+        let cases: &[&TestCase] = &[&test];
+        let owned = cases[0].to_test();
+        assert!(owned.desc == test.desc);
+        match owned.testfn {
+            StaticTestFn(g) => assert!(g as fn() == f as fn()),
+            _ => panic!("expected a static test function"),
+        }
+    }
+
     #[test]
     pub fn sort_tests() {
         let mut opts = TestOpts::new();
@@ -1854,6 +2720,7 @@ mod tests {
                         name: DynTestName((*name).clone()),
                         ignore: false,
                         should_panic: ShouldPanic::No,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(move |()| testfn())),
                 };
@@ -75,20 +75,13 @@ typedef Archive::Child *LLVMRustArchiveChildRef;
 typedef Archive::Child const *LLVMRustArchiveChildConstRef;
 typedef RustArchiveIterator *LLVMRustArchiveIteratorRef;
 
-extern "C" LLVMRustArchiveRef LLVMRustOpenArchive(char *Path) {
-  ErrorOr<std::unique_ptr<MemoryBuffer>> BufOr =
-      MemoryBuffer::getFile(Path, -1, false);
-  if (!BufOr) {
-    LLVMRustSetLastError(BufOr.getError().message().c_str());
-    return nullptr;
-  }
-
+static LLVMRustArchiveRef openArchive(std::unique_ptr<MemoryBuffer> Buf) {
 #if LLVM_VERSION_LE(3, 8)
   ErrorOr<std::unique_ptr<Archive>> ArchiveOr =
 #else
   Expected<std::unique_ptr<Archive>> ArchiveOr =
 #endif
-      Archive::create(BufOr.get()->getMemBufferRef());
+      Archive::create(Buf->getMemBufferRef());
 
   if (!ArchiveOr) {
 #if LLVM_VERSION_LE(3, 8)
@@ -100,11 +93,26 @@ extern "C" LLVMRustArchiveRef LLVMRustOpenArchive(char *Path) {
   }
 
   OwningBinary<Archive> *Ret = new OwningBinary<Archive>(
-      std::move(ArchiveOr.get()), std::move(BufOr.get()));
+      std::move(ArchiveOr.get()), std::move(Buf));
 
   return Ret;
 }
 
+extern "C" LLVMRustArchiveRef LLVMRustOpenArchive(char *Path) {
+  ErrorOr<std::unique_ptr<MemoryBuffer>> BufOr =
+      MemoryBuffer::getFile(Path, -1, false);
+  if (!BufOr) {
+    LLVMRustSetLastError(BufOr.getError().message().c_str());
+    return nullptr;
+  }
+  return openArchive(std::move(BufOr.get()));
+}
+
+extern "C" LLVMRustArchiveRef LLVMRustOpenArchiveFromBuffer(const char *Data,
+                                                            size_t Len) {
+  return openArchive(MemoryBuffer::getMemBufferCopy(StringRef(Data, Len)));
+}
+
 extern "C" void LLVMRustDestroyArchive(LLVMRustArchiveRef RustArchive) {
   delete RustArchive;
 }
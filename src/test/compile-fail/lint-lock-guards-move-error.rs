@@ -0,0 +1,23 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a borrowck error in a function taking a lock is reported as such,
+// rather than making the lock guard lints stumble on the invalid MIR.
+
+#![deny(double_lock)]
+
+use std::sync::Mutex;
+
+fn take(m: &Mutex<String>, s: &String) -> String {
+    let a = m.lock().unwrap();
+    *s //~ ERROR cannot move out of borrowed content
+}
+
+fn main() {}
@@ -0,0 +1,86 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(double_lock, lock_across_blocking_call)]
+#![allow(unused_variables)]
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::Receiver;
+
+struct State {
+    counter: Mutex<u32>,
+    other: Mutex<u32>,
+}
+
+fn relock_field(state: &State) {
+    let a = state.counter.lock().unwrap();
+    let b = state.counter.lock().unwrap(); //~ ERROR: this lock is already held by this thread
+}
+
+fn relock_through_arc(m: Arc<Mutex<u32>>) {
+    let a = m.lock().unwrap();
+    let b = m.lock(); //~ ERROR: this lock is already held by this thread
+}
+
+fn relock_rwlock(l: &RwLock<u32>) {
+    let a = l.write().unwrap();
+    let b = l.write(); //~ ERROR: this lock is already held by this thread
+}
+
+fn read_while_reading(l: &RwLock<u32>) {
+    let a = l.read().unwrap();
+    let b = l.read();
+}
+
+fn write_while_reading(l: &RwLock<u32>) {
+    let a = l.read().unwrap();
+    let b = l.write(); //~ ERROR: this lock is already held by this thread
+}
+
+fn read_while_writing(l: &RwLock<u32>) {
+    let a = l.write().unwrap();
+    let b = l.read(); //~ ERROR: this lock is already held by this thread
+}
+
+fn relock_after_drop(state: &State) {
+    let a = state.counter.lock().unwrap();
+    drop(a);
+    let b = state.counter.lock().unwrap();
+}
+
+fn relock_in_scope(state: &State) {
+    {
+        let a = state.counter.lock().unwrap();
+    }
+    let b = state.counter.lock().unwrap();
+}
+
+fn lock_other(state: &State) {
+    let a = state.counter.lock().unwrap();
+    let b = state.other.lock().unwrap();
+}
+
+fn lock_in_loop(state: &State) {
+    for _ in 0..2 {
+        *state.counter.lock().unwrap() += 1;
+    }
+}
+
+fn recv_with_lock(state: &State, rx: &Receiver<u32>) {
+    let a = state.counter.lock().unwrap();
+    let x = rx.recv(); //~ ERROR: blocking call while a lock is held
+}
+
+fn recv_without_lock(state: &State, rx: &Receiver<u32>) {
+    let x = *state.counter.lock().unwrap();
+    let y = rx.recv();
+}
+
+fn main() {}
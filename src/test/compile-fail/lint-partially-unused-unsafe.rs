@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(partially_unused_unsafe)]
+#![allow(dead_code)]
+
+unsafe fn f() -> u32 { 0 }
+fn g(x: u32) -> u32 { x }
+
+fn all_needed(p: *const u32) -> u32 {
+    unsafe {
+        let x = *p;
+        f() + x
+    }
+}
+
+fn partially_needed(p: *const u32) -> u32 {
+    unsafe { //~ ERROR: `unsafe` block holds code that doesn't need `unsafe`
+        let x = *p;
+        let y = g(x);
+        g(y)
+    }
+}
+
+fn needed_in_closure(p: *const u32) -> u32 {
+    unsafe {
+        let x = (|| *p)();
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(undocumented_unsafe)]
+#![allow(dead_code)]
+
+macro_rules! unsafe_in_macro {
+    ($p:expr) => { unsafe { *$p } }
+}
+
+fn documented(p: *const u8) -> u8 {
+    // SAFETY: `p` is valid for reads, as the callers check.
+    unsafe { *p }
+}
+
+fn documented_above_statement(p: *const u8) -> u8 {
+    // The value is read once.
+    // SAFETY: `p` is valid for reads, as the callers check.
+    let x = unsafe { *p };
+    x
+}
+
+fn documented_on_the_line(p: *const u8) -> u8 {
+    unsafe { *p } // SAFETY: `p` is valid for reads, as the callers check.
+}
+
+fn documented_after_a_url(p: *const u8) -> u8 {
+    let _u = "http://x"; unsafe { *p } // SAFETY: `p` is valid for reads, as the callers check.
+}
+
+fn documented_in_block_comment(p: *const u8) -> u8 {
+    /*
+     * SAFETY: `p` is valid for reads, as the callers check.
+     */
+    unsafe { *p }
+}
+
+fn undocumented(p: *const u8) -> u8 {
+    unsafe { *p } //~ ERROR: `unsafe` block without a
+}
+
+fn separated_by_code(p: *const u8) -> u8 {
+    // SAFETY: `p` is valid for reads, as the callers check.
+    let q = p;
+    unsafe { *q } //~ ERROR: `unsafe` block without a
+}
+
+fn not_a_comment(p: *const u8) -> &'static str {
+    unsafe { *p; "SAFETY: fine" } //~ ERROR: `unsafe` block without a
+}
+
+fn from_macro(p: *const u8) -> u8 {
+    unsafe_in_macro!(p)
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(fn_must_use)]
+#![deny(unused_must_use)]
+#![allow(dead_code)]
+
+struct Builder;
+
+impl Builder {
+    #[must_use = "the built value is dropped"]
+    fn finish(&self) -> u32 { 0 }
+
+    #[must_use]
+    fn is_empty(&self) -> bool { true }
+}
+
+trait Split {
+    #[must_use]
+    fn split_off(&mut self, at: usize) -> Self;
+}
+
+struct Buffer(Vec<u8>);
+
+impl Split for Buffer {
+    fn split_off(&mut self, at: usize) -> Buffer { Buffer(self.0.split_off(at)) }
+}
+
+#[must_use]
+fn compute() -> u32 { 0 }
+
+fn plain() -> u32 { 0 }
+
+fn main() {
+    let b = Builder;
+    let mut v = Buffer(vec![1, 2, 3]);
+
+    compute(); //~ ERROR: unused return value of `compute` which must be used
+    b.finish(); //~ ERROR: unused return value of `Builder::finish` which must be used: the built
+    Builder::finish(&b); //~ ERROR: unused return value of `Builder::finish` which must be used
+    b.is_empty(); //~ ERROR: unused return value of `Builder::is_empty` which must be used
+    Split::split_off(&mut v, 1); //~ ERROR: unused return value of `Split::split_off` which must
+    v.split_off(1); //~ ERROR: unused return value of `Split::split_off` which must be used
+
+    plain();
+    let _ = compute();
+    let _ = b.finish();
+    let _x = v.split_off(0);
+}
@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Checks error codes, span labels and suggested replacements through the
+// structured forms of the `//~` annotations.
+
+fn escaping_closure<'a>(x: &'a i32) -> Box<FnMut() + 'a> {
+    let mut books = vec![1, 2, 3];
+    Box::new(|| books.push(4))
+    //~^ ERROR[E0373] closure may outlive the current function
+    //~| LABEL `books` is borrowed here
+    //~| LABEL may outlive borrowed value `books`
+    //~| REPLACE move || books.push(4)
+}
+
+fn main() {
+    let mut v = vec![Some("foo"), Some("bar")];
+    v.push(v.pop().unwrap());
+    //~^ ERROR[E0499] cannot borrow `v` as mutable more than once at a time
+    //~| LABEL first mutable borrow occurs here
+    //~| LABEL second mutable borrow occurs here
+    //~| LABEL first borrow ends here
+}
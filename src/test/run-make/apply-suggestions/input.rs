@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::HashMap;
+// Only some of these are unused, which is left alone.
+use std::fmt::{Debug, Display};
+
+pub fn sum(values: &[u32]) -> u32 {
+    let mut total = 0;
+    for v in values {
+        total += *v;
+    }
+    let mut doubled = total * 2;
+    doubled
+}
+
+pub fn show<T: Display>(x: T) -> String {
+    format!("{}", x)
+}
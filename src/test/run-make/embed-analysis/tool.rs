@@ -0,0 +1,107 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+
+use rustc_driver::embed::{Compiler, ItemKind, Location, Signature, TypedExpr};
+
+use std::io;
+use std::path::PathBuf;
+
+const LIB: &'static str = "mod shapes;
+pub fn area(w: u32, h: u32) -> u32 {
+    let a = w * h;
+    a
+}
+use shapes::Square;
+pub trait Shape {
+    type Unit;
+    fn sides(&self) -> u32;
+}
+impl Shape for Square {
+    type Unit = u32;
+    fn sides(&self) -> u32 { 4 }
+}
+";
+
+const SHAPES: &'static str = "pub struct Square(pub u32);
+impl Square {
+    pub fn side(&self) -> u32 { self.0 }
+}
+";
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let mut sysroot = PathBuf::from(&args[1]);
+    sysroot.pop();
+    sysroot.pop();
+    let sysroot = sysroot.to_str().unwrap().to_string();
+
+    let result = rustc_driver::in_rustc_thread(move || {
+        let mut compiler = Compiler::new("lib.rs");
+        compiler.arg("--crate-type=lib")
+                .arg("--sysroot")
+                .arg(sysroot.clone())
+                .source("lib.rs", LIB)
+                .source("shapes.rs", SHAPES);
+        let (name, items, signature, exprs, types) = compiler.analyze(|krate| {
+            let items = krate.items();
+            let area = items.iter().find(|item| item.path == "area").unwrap().id;
+            // Every item can be asked about, whether it has a type or not.
+            let types = items.iter().map(|item| {
+                let _ = krate.signature(item.id);
+                (item.path.clone(), krate.type_of(item.id))
+            }).collect::<Vec<_>>();
+            (krate.name(), items.clone(), krate.signature(area), krate.expressions(area), types)
+        }).unwrap();
+
+        assert_eq!(name, "lib");
+        let square = items.iter().find(|item| item.path == "shapes::Square").unwrap();
+        assert_eq!(square.kind, ItemKind::Struct);
+        assert_eq!(square.location.file, "shapes.rs");
+        assert_eq!(square.location.line, 1);
+        assert!(items.iter().any(|item| item.name == "side" && item.kind == ItemKind::Method));
+        assert_eq!(signature, Some(Signature {
+            inputs: vec!["u32".to_string(), "u32".to_string()],
+            output: "u32".to_string(),
+        }));
+        let type_of = |path: &str| {
+            types.iter().find(|&&(ref p, _)| p == path).unwrap().1.clone()
+        };
+        assert_eq!(type_of("shapes"), None);
+        assert_eq!(type_of("Shape"), None);
+        assert_eq!(type_of("Shape::Unit"), None);
+        assert_eq!(type_of("shapes::Square"), Some("shapes::Square".to_string()));
+        assert!(type_of("area").is_some());
+        assert!(exprs.contains(&TypedExpr {
+            location: Location {
+                file: "lib.rs".to_string(),
+                line: 3,
+                column: 13,
+                end_line: 3,
+                end_column: 18,
+            },
+            ty: "u32".to_string(),
+        }));
+
+        // The closure isn't called when there are errors.
+        let mut compiler = Compiler::new("bad.rs");
+        compiler.arg("--crate-type=lib")
+                .arg("--sysroot")
+                .arg(sysroot)
+                .source("bad.rs", "pub fn f() -> u32 { true }")
+                .diagnostics(Box::new(io::sink()));
+        let error = compiler.analyze(|_| panic!("analyzed a crate with errors")).unwrap_err();
+        assert_eq!(error.errors, 1);
+    });
+    assert!(result.is_ok());
+}
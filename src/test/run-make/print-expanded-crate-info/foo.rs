@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+mod bar;
+
+// There is no such file, but it is never looked for either.
+#[cfg(never)]
+mod missing;
+
+#[link(name = "quux")]
+extern {}
+
+#[cfg(never)]
+#[link(name = "gone")]
+extern {}
+
+const DATA: &'static str = include_str!("data.txt");
+
+fn main() {
+    println!("{} {}", bar::BAR, DATA);
+}
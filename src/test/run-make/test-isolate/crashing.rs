@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::process;
+
+#[test]
+fn passes() {}
+
+#[test]
+fn exits() {
+    println!("printed before exiting");
+    process::exit(3);
+}
+
+#[test]
+fn aborts() {
+    process::abort();
+}
+
+#[test]
+fn panics() {
+    panic!("test failed normally");
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn expected_panic() {
+    panic!("boom");
+}
@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![allow(unused_unsafe)]
+
+static mut COUNTER: u32 = 0;
+
+pub unsafe fn bump() -> u32 {
+    COUNTER += 1;
+    COUNTER
+}
+
+pub fn read(p: *const u32) -> u32 {
+    unsafe { *p + bump() }
+}
+
+pub fn not_needed() -> u32 {
+    unsafe { 1 }
+}
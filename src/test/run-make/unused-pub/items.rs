@@ -0,0 +1,32 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+pub struct Used;
+pub struct Unused;
+pub struct Tuple(pub u32);
+
+impl Used {
+    pub fn new() -> Used { Used }
+    pub fn unused_method(&self) {}
+}
+
+pub enum Kind { A, B }
+
+pub fn used_fn() -> Kind { helper(); Kind::A }
+pub fn helper() {}
+pub fn unused_fn() {}
+pub fn recursive(n: u32) -> u32 { if n == 0 { 0 } else { recursive(n - 1) } }
+
+pub mod inner {
+    pub const USED: u32 = 1;
+    pub fn unused_inner() {}
+}
@@ -0,0 +1,135 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, box_syntax)]
+
+extern crate rustc_driver;
+extern crate syntax;
+
+use rustc_driver::RustcDefaultCalls;
+use syntax::codemap::{FileLoader, RealFileLoader};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+const MAIN: &'static str = r#"
+extern crate dep;
+
+mod sub;
+
+pub static TEXT: &'static str = include_str!("data/text.txt");
+pub static BYTES: &'static [u8; 3] = include_bytes!("data/bytes.bin");
+
+pub fn check() -> u32 {
+    dep::answer() + sub::one()
+}
+"#;
+
+// Files under `/virtual` only exist in memory; the others are read from disk.
+struct VirtualLoader {
+    files: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl VirtualLoader {
+    fn is_virtual(&self, path: &Path) -> bool {
+        path.starts_with("/virtual")
+    }
+}
+
+impl FileLoader for VirtualLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        if self.is_virtual(path) {
+            self.files.contains_key(path)
+        } else {
+            RealFileLoader.file_exists(path)
+        }
+    }
+
+    fn abs_path(&self, path: &Path) -> Option<PathBuf> {
+        RealFileLoader.abs_path(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.read_binary_file(path).and_then(|bytes| {
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if !self.is_virtual(path) {
+            return RealFileLoader.read_binary_file(path);
+        }
+        match self.files.get(path) {
+            Some(bytes) => Ok(bytes.clone()),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, "not in memory")),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.is_virtual(path) {
+            return RealFileLoader.read_dir(path);
+        }
+        Ok(self.files.keys().filter(|file| file.parent() == Some(path)).cloned().collect())
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.is_virtual(path) {
+            Ok(path.to_path_buf())
+        } else {
+            RealFileLoader.canonicalize(path)
+        }
+    }
+
+    fn real_path(&self, path: &Path) -> Option<PathBuf> {
+        if self.is_virtual(path) {
+            None
+        } else {
+            RealFileLoader.real_path(path)
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let mut sysroot = PathBuf::from(&args[1]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut rlib = Vec::new();
+    File::open(&args[2]).unwrap().read_to_end(&mut rlib).unwrap();
+
+    let mut files = HashMap::new();
+    files.insert(PathBuf::from("/virtual/main.rs"), MAIN.as_bytes().to_vec());
+    files.insert(PathBuf::from("/virtual/sub.rs"), b"pub fn one() -> u32 { 1 }".to_vec());
+    files.insert(PathBuf::from("/virtual/data/text.txt"), b"hello".to_vec());
+    files.insert(PathBuf::from("/virtual/data/bytes.bin"), vec![0xff, 0, 1]);
+    files.insert(PathBuf::from("/virtual/deps/libdep.rlib"), rlib);
+
+    let args = vec!["rustc".to_string(),
+                    "/virtual/main.rs".to_string(),
+                    "--crate-type=lib".to_string(),
+                    "--emit=metadata".to_string(),
+                    "--out-dir".to_string(),
+                    args[3].clone(),
+                    "-L".to_string(),
+                    "/virtual/deps".to_string(),
+                    "--sysroot".to_string(),
+                    sysroot.to_str().unwrap().to_string()];
+    let result = rustc_driver::in_rustc_thread(move || {
+        let loader: Box<FileLoader> = box VirtualLoader { files: files };
+        rustc_driver::run_compiler(&args, &mut RustcDefaultCalls, Some(loader), None).0
+    });
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(n)) => panic!("{} errors", n),
+        Err(_) => panic!("the compiler panicked"),
+    }
+}
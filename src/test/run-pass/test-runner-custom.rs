@@ -0,0 +1,52 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+#![feature(custom_test_frameworks)]
+#![test_runner = "runner"]
+
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+static RAN: AtomicUsize = ATOMIC_USIZE_INIT;
+
+pub trait Case {
+    fn name(&self) -> &'static str;
+    fn run(&self);
+}
+
+pub struct Double(&'static str, u32, u32);
+
+impl Case for Double {
+    fn name(&self) -> &'static str { self.0 }
+    fn run(&self) {
+        assert_eq!(self.1 * 2, self.2);
+        RAN.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test_case]
+static TWO: Double = Double("two", 2, 4);
+
+mod nested {
+    use super::Double;
+
+    #[test_case]
+    const ZERO: Double = Double("zero", 0, 0);
+}
+
+fn runner(cases: &[&Case]) {
+    let names = cases.iter().map(|case| case.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["two", "zero"]);
+    for case in cases {
+        case.run();
+    }
+    assert_eq!(RAN.load(Ordering::SeqCst), 2);
+}
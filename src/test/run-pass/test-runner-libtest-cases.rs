@@ -0,0 +1,47 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+#![feature(custom_test_frameworks, test)]
+#![test_runner = "test::test_main_cases"]
+
+extern crate test;
+
+use test::{TestCase, TestDesc, TestDescAndFn, StaticTestName, ShouldPanic, DynTestFn};
+
+// A table-driven test, run by libtest like any `#[test]` function
+pub struct Parses(&'static str, &'static str, i32);
+
+impl TestCase for Parses {
+    fn to_test(&self) -> TestDescAndFn {
+        let (input, expected) = (self.1, self.2);
+        TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName(self.0),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                timeout: None,
+            },
+            testfn: DynTestFn(Box::new(move |()| {
+                assert_eq!(input.parse::<i32>().unwrap(), expected);
+            })),
+        }
+    }
+}
+
+#[test_case]
+const POSITIVE: Parses = Parses("positive", "42", 42);
+
+#[test_case]
+const NEGATIVE: Parses = Parses("negative", "-7", -7);
+
+#[test]
+fn ordinary_test() {}
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![warn(arithmetic_overflow)]
+#![allow(unused_variables)]
+
+fn main() {
+    let x: u8 = 200;
+    let y = x + 100;
+
+    let n: u32 = 5;
+    let m = n - 6;
+
+    let s: u32 = 40;
+    let t = 1u32 << s;
+
+    let c = std::env::args().count() > 1;
+    let z: u8 = if c { 10 } else { 250 };
+    let w = z + 10;
+
+    // These never overflow.
+    let a: u8 = 100;
+    let b = a + 100;
+    let mut i: u8 = 0;
+    while i < 10 {
+        i += 1;
+    }
+    let mut p: u8 = 255;
+    {
+        let r = &mut p;
+        *r = 0;
+    }
+    let q = p + 1;
+}
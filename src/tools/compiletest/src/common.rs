@@ -185,6 +185,9 @@ pub struct Config {
     // Print one character per test instead of one line
     pub quiet: bool,
 
+    // Overwrite the expected output of UI and MIR-opt tests with the actual output
+    pub bless: bool,
+
     // where to find the qemu test client process, if we're using it
     pub qemu_test_client: Option<PathBuf>,
 
@@ -20,7 +20,9 @@ use std::str::FromStr;
 pub enum ErrorKind {
     Help,
     Error,
+    Label,
     Note,
+    Replacement,
     Suggestion,
     Warning,
 }
@@ -33,7 +35,9 @@ impl FromStr for ErrorKind {
         match part0 {
             "HELP" => Ok(ErrorKind::Help),
             "ERROR" => Ok(ErrorKind::Error),
+            "LABEL" => Ok(ErrorKind::Label),
             "NOTE" => Ok(ErrorKind::Note),
+            "REPLACE" => Ok(ErrorKind::Replacement),
             "SUGGESTION" => Ok(ErrorKind::Suggestion),
             "WARN" => Ok(ErrorKind::Warning),
             "WARNING" => Ok(ErrorKind::Warning),
@@ -47,7 +51,9 @@ impl fmt::Display for ErrorKind {
         match *self {
             ErrorKind::Help => write!(f, "help"),
             ErrorKind::Error => write!(f, "error"),
+            ErrorKind::Label => write!(f, "label"),
             ErrorKind::Note => write!(f, "note"),
+            ErrorKind::Replacement => write!(f, "replacement"),
             ErrorKind::Suggestion => write!(f, "suggestion"),
             ErrorKind::Warning => write!(f, "warning"),
         }
@@ -60,6 +66,9 @@ pub struct Error {
     /// What kind of message we expect (e.g. warning, error, suggestion).
     /// `None` if not specified or unknown message kind.
     pub kind: Option<ErrorKind>,
+    /// The error code, as in `//~ ERROR[E0308]`. When expected, the
+    /// message must carry exactly this code.
+    pub code: Option<String>,
     pub msg: String,
 }
 
@@ -78,6 +87,11 @@ enum WhichLine {
 /// and also //~^ ERROR message one for the preceding line, and
 ///          //~| ERROR message two for that same line.
 ///
+/// The kind may carry an error code, as in //~ ERROR[E0499] message.
+/// Besides the usual message kinds, //~ LABEL matches the label of a
+/// (primary or secondary) span starting on the line, and //~ REPLACE
+/// matches the replacement text a suggestion offers for a span there.
+///
 /// If cfg is not None (i.e., in an incremental test), then we look
 /// for `//[X]~` instead, where `X` is the current `cfg`.
 pub fn load_errors(testfile: &Path, cfg: Option<&str>) -> Vec<Error> {
@@ -128,15 +142,16 @@ fn parse_expected(last_nonfollow_error: Option<usize>,
         (false, line[start + tag.len()..].chars().take_while(|c| *c == '^').count())
     };
     let kind_start = start + tag.len() + adjusts + (follow as usize);
-    let (kind, msg);
-    match line[kind_start..]
+    let (kind, code, msg);
+    let (kind_str, code_str) = split_code(line[kind_start..]
         .split_whitespace()
         .next()
-        .expect("Encountered unexpected empty comment")
-        .parse::<ErrorKind>() {
+        .expect("Encountered unexpected empty comment"));
+    match kind_str.parse::<ErrorKind>() {
         Ok(k) => {
             // If we find `//~ ERROR foo` or something like that:
             kind = Some(k);
+            code = code_str.map(|c| c.to_owned());
             let letters = line[kind_start..].chars();
             msg = letters.skip_while(|c| c.is_whitespace())
                 .skip_while(|c| !c.is_whitespace())
@@ -145,6 +160,7 @@ fn parse_expected(last_nonfollow_error: Option<usize>,
         Err(_) => {
             // Otherwise we found `//~ foo`:
             kind = None;
+            code = None;
             let letters = line[kind_start..].chars();
             msg = letters.skip_while(|c| c.is_whitespace())
                 .collect::<String>();
@@ -167,16 +183,26 @@ fn parse_expected(last_nonfollow_error: Option<usize>,
         (which, line_num)
     };
 
-    debug!("line={} tag={:?} which={:?} kind={:?} msg={:?}",
+    debug!("line={} tag={:?} which={:?} kind={:?} code={:?} msg={:?}",
            line_num,
            tag,
            which,
            kind,
+           code,
            msg);
     Some((which,
           Error {
         line_num: line_num,
         kind: kind,
+        code: code,
         msg: msg,
     }))
 }
+
+/// Splits `ERROR[E0308]` into `ERROR` and `E0308`.
+fn split_code(kind: &str) -> (&str, Option<&str>) {
+    match kind.find('[') {
+        Some(i) if kind.ends_with(']') => (&kind[..i], Some(&kind[i + 1..kind.len() - 1])),
+        _ => (kind, None),
+    }
+}
@@ -36,6 +36,7 @@ struct DiagnosticSpan {
     column_end: usize,
     is_primary: bool,
     label: Option<String>,
+    suggested_replacement: Option<String>,
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
 
@@ -141,6 +142,7 @@ fn push_expected_errors(expected_errors: &mut Vec<Error>,
     // Convert multi-line messages into multiple expected
     // errors. We expect to replace these with something
     // more structured shortly anyhow.
+    let code = diagnostic.code.as_ref().map(|c| c.code.clone());
     let mut message_lines = diagnostic.message.lines();
     if let Some(first_line) = message_lines.next() {
         for span in primary_spans {
@@ -149,6 +151,7 @@ fn push_expected_errors(expected_errors: &mut Vec<Error>,
             expected_errors.push(Error {
                 line_num: span.line_start,
                 kind: kind,
+                code: code.clone(),
                 msg: msg,
             });
         }
@@ -158,6 +161,7 @@ fn push_expected_errors(expected_errors: &mut Vec<Error>,
             expected_errors.push(Error {
                 line_num: span.line_start,
                 kind: None,
+                code: code.clone(),
                 msg: with_code(span, next_line),
             });
         }
@@ -171,11 +175,25 @@ fn push_expected_errors(expected_errors: &mut Vec<Error>,
             expected_errors.push(Error {
                 line_num: start_line + index,
                 kind: Some(ErrorKind::Suggestion),
+                code: None,
                 msg: line.to_string(),
             });
         }
     }
 
+    // Register the text suggested for each span separately, so it can be
+    // checked without depending on how the whole suggestion renders.
+    for span in &spans_in_this_file {
+        if let Some(ref replacement) = span.suggested_replacement {
+            expected_errors.push(Error {
+                line_num: span.line_start,
+                kind: Some(ErrorKind::Replacement),
+                code: None,
+                msg: replacement.clone(),
+            });
+        }
+    }
+
     // Add notes for the backtrace
     for span in primary_spans {
         for frame in &span.expansion {
@@ -183,12 +201,21 @@ fn push_expected_errors(expected_errors: &mut Vec<Error>,
         }
     }
 
-    // Add notes for any labels that appear in the message.
+    // Add notes for any labels that appear in the message. Each label is
+    // registered a second time as such, for tests that check them with
+    // `//~ LABEL` rather than as notes.
     for span in spans_in_this_file.iter()
         .filter(|span| span.label.is_some()) {
         expected_errors.push(Error {
             line_num: span.line_start,
             kind: Some(ErrorKind::Note),
+            code: None,
+            msg: span.label.clone().unwrap(),
+        });
+        expected_errors.push(Error {
+            line_num: span.line_start,
+            kind: Some(ErrorKind::Label),
+            code: code.clone(),
             msg: span.label.clone().unwrap(),
         });
     }
@@ -206,6 +233,7 @@ fn push_backtrace(expected_errors: &mut Vec<Error>,
         expected_errors.push(Error {
             line_num: expansion.span.line_start,
             kind: Some(ErrorKind::Note),
+            code: None,
             msg: format!("in this expansion of {}", expansion.macro_decl_name),
         });
     }
@@ -90,6 +90,8 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           optopt("", "target-rustcflags", "flags to pass to rustc for target", "FLAGS"),
           optflag("", "verbose", "run tests verbosely, showing all output"),
           optflag("", "quiet", "print one character per test instead of one line"),
+          optflag("", "bless", "overwrite the expected output of UI and MIR-opt tests \
+                                with the actual output"),
           optopt("", "logfile", "file to log test execution to", "FILE"),
           optopt("", "target", "the target to build for", "TARGET"),
           optopt("", "host", "the host to build for", "HOST"),
@@ -187,6 +189,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
         lldb_python_dir: matches.opt_str("lldb-python-dir"),
         verbose: matches.opt_present("verbose"),
         quiet: matches.opt_present("quiet"),
+        bless: matches.opt_present("bless"),
         qemu_test_client: matches.opt_str("qemu-test-client").map(PathBuf::from),
 
         cc: matches.opt_str("cc").unwrap(),
@@ -230,6 +233,7 @@ pub fn log_config(config: &Config) {
                     config.adb_device_status));
     logv(c, format!("verbose: {}", config.verbose));
     logv(c, format!("quiet: {}", config.quiet));
+    logv(c, format!("bless: {}", config.bless));
     logv(c, format!("\n"));
 }
 
@@ -340,15 +344,27 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         run_ignored: config.run_ignored,
         quiet: config.quiet,
         logfile: config.logfile.clone(),
+        junit: None,
         run_tests: true,
         bench_benchmarks: true,
         nocapture: match env::var("RUST_TEST_NOCAPTURE") {
             Ok(val) => &val != "0",
             Err(_) => false
         },
+        isolate: false,
         color: test::AutoColor,
+        format: test::OutputFormat::Pretty,
         test_threads: None,
+        test_timeout: None,
         skip: vec![],
+        shuffle_seed: None,
+        shard: None,
+        save_baseline: None,
+        baseline: None,
+        report_time: false,
+        slowest: 0,
+        warn_time: None,
+        fail_time: None,
         list: false,
     }
 }
@@ -479,6 +495,7 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> test::TestDescAndFn
             name: make_test_name(config, testpaths),
             ignore: ignore,
             should_panic: should_panic,
+            timeout: None,
         },
         testfn: make_test_closure(config, testpaths),
     }
@@ -99,6 +99,15 @@ struct DebuggerCommands {
     breakpoint_lines: Vec<usize>,
 }
 
+/// The kinds of optional messages a test has annotations for. Messages of
+/// these kinds must then all be annotated.
+struct ExpectedKinds {
+    help: bool,
+    note: bool,
+    label: bool,
+    replacement: bool,
+}
+
 impl<'test> TestCx<'test> {
     /// invoked once before any revisions have been processed
     fn init_all(&self) {
@@ -1048,9 +1057,17 @@ actual:\n\
         // If the testcase being checked contains at least one expected "help"
         // message, then we'll ensure that all "help" messages are expected.
         // Otherwise, all "help" messages reported by the compiler will be ignored.
-        // This logic also applies to "note" messages.
-        let expect_help = expected_errors.iter().any(|ee| ee.kind == Some(ErrorKind::Help));
-        let expect_note = expected_errors.iter().any(|ee| ee.kind == Some(ErrorKind::Note));
+        // This logic also applies to "note" messages, span labels and suggested
+        // replacements.
+        let expects = |kind: ErrorKind| {
+            expected_errors.iter().any(|ee| ee.kind.as_ref() == Some(&kind))
+        };
+        let expected_kinds = ExpectedKinds {
+            help: expects(ErrorKind::Help),
+            note: expects(ErrorKind::Note),
+            label: expects(ErrorKind::Label),
+            replacement: expects(ErrorKind::Replacement),
+        };
 
         // Parse the JSON output from the compiler and extract out the messages.
         let actual_errors = json::parse_output(&file_name, &proc_res.stderr, &proc_res);
@@ -1066,6 +1083,8 @@ actual:\n\
                         actual_error.line_num == expected_error.line_num &&
                         (expected_error.kind.is_none() ||
                          actual_error.kind == expected_error.kind) &&
+                        (expected_error.code.is_none() ||
+                         actual_error.code == expected_error.code) &&
                         actual_error.msg.contains(&expected_error.msg)
                 });
 
@@ -1077,7 +1096,7 @@ actual:\n\
                 }
 
                 None => {
-                    if self.is_unexpected_compiler_message(actual_error, expect_help, expect_note) {
+                    if self.is_unexpected_compiler_message(actual_error, &expected_kinds) {
                         self.error(
                             &format!("{}:{}: unexpected {:?}: '{}'",
                                      file_name,
@@ -1097,12 +1116,15 @@ actual:\n\
         for (index, expected_error) in expected_errors.iter().enumerate() {
             if !found[index] {
                 self.error(
-                    &format!("{}:{}: expected {} not found: {}",
+                    &format!("{}:{}: expected {}{} not found: {}",
                              file_name,
                              expected_error.line_num,
                              expected_error.kind.as_ref()
                              .map_or("message".into(),
                                      |k| k.to_string()),
+                             expected_error.code.as_ref()
+                             .map_or(String::new(),
+                                     |c| format!("[{}]", c)),
                              expected_error.msg));
                 not_found.push(expected_error.clone());
             }
@@ -1127,15 +1149,16 @@ actual:\n\
     /// Returns true if we should report an error about `actual_error`,
     /// which did not match any of the expected error. We always require
     /// errors/warnings to be explicitly listed, but only require
-    /// helps/notes if there are explicit helps/notes given.
+    /// helps/notes/labels/replacements if there are explicit ones given.
     fn is_unexpected_compiler_message(&self,
                                       actual_error: &Error,
-                                      expect_help: bool,
-                                      expect_note: bool)
+                                      expected_kinds: &ExpectedKinds)
                                       -> bool {
         match actual_error.kind {
-            Some(ErrorKind::Help) => expect_help,
-            Some(ErrorKind::Note) => expect_note,
+            Some(ErrorKind::Help) => expected_kinds.help,
+            Some(ErrorKind::Note) => expected_kinds.note,
+            Some(ErrorKind::Label) => expected_kinds.label,
+            Some(ErrorKind::Replacement) => expected_kinds.replacement,
             Some(ErrorKind::Error) => true,
             Some(ErrorKind::Warning) => true,
             Some(ErrorKind::Suggestion) => false,
@@ -2394,11 +2417,14 @@ actual:\n\
         let normalized_stderr = self.normalize_output(&proc_res.stderr);
 
         let mut errors = 0;
-        errors += self.compare_output("stdout", &normalized_stdout, &expected_stdout);
-        errors += self.compare_output("stderr", &normalized_stderr, &expected_stderr);
+        errors += self.compare_output("stdout", &normalized_stdout,
+                                      &expected_stdout_path, &expected_stdout);
+        errors += self.compare_output("stderr", &normalized_stderr,
+                                      &expected_stderr_path, &expected_stderr);
 
         if errors > 0 {
-            println!("To update references, run this command from build directory:");
+            println!("To update references, rerun the tests with `--bless`, or run this \
+                      command from build directory:");
             let relative_path_to_file =
                 self.testpaths.relative_dir
                               .join(self.testpaths.file.file_name().unwrap());
@@ -2432,21 +2458,39 @@ actual:\n\
                                                    .read_to_string(&mut test_file_contents)
                                                    .unwrap();
         if let Some(idx) =  test_file_contents.find("// END RUST SOURCE") {
-            let (_, tests_text) = test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
+            let (source, tests_text) =
+                test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
             let tests_text_str = String::from(tests_text);
             let mut curr_test : Option<&str> = None;
             let mut curr_test_contents = Vec::new();
+            // The expected blocks as they should be after blessing, and where
+            // the contents of the current one start
+            let mut blessed_lines = Vec::new();
+            let mut curr_test_start = 0;
+            let mut blessed_any = false;
             for l in tests_text_str.lines() {
                 debug!("line: {:?}", l);
                 if l.starts_with("// START ") {
                     let (_, t) = l.split_at("// START ".len());
                     curr_test = Some(t);
+                    curr_test_start = blessed_lines.len() + 1;
                 } else if l.starts_with("// END") {
                     let (_, t) = l.split_at("// END ".len());
                     if Some(t) != curr_test {
                         panic!("mismatched START END test name");
                     }
-                    self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents);
+                    let dumped = self.load_mir_dump(t);
+                    if let Err(msg) = compare_mir_test_output(&curr_test_contents, &dumped) {
+                        if !self.config.bless {
+                            panic!("{}", msg);
+                        }
+                        blessed_lines.truncate(curr_test_start);
+                        blessed_lines.extend(dumped.lines()
+                                                   .map(nocomment_mir_line)
+                                                   .filter(|l| !l.is_empty())
+                                                   .map(|l| format!("// {}", l.trim_right())));
+                        blessed_any = true;
+                    }
                     curr_test = None;
                     curr_test_contents.clear();
                 } else if l.is_empty() {
@@ -2455,6 +2499,19 @@ actual:\n\
                     let (_, test_content) = l.split_at("// ".len());
                     curr_test_contents.push(test_content);
                 }
+                blessed_lines.push(l.to_string());
+            }
+
+            if blessed_any {
+                let mut blessed = format!("{}{}", source, blessed_lines.join("\n"));
+                if tests_text.ends_with('\n') {
+                    blessed.push('\n');
+                }
+                if let Err(e) = File::create(&self.testpaths.file)
+                                     .and_then(|mut f| f.write_all(blessed.as_bytes())) {
+                    self.fatal(&format!("failed to bless `{}`: {}",
+                                        self.testpaths.file.display(), e));
+                }
             }
         }
     }
@@ -2471,52 +2528,17 @@ actual:\n\
         }
     }
 
-    fn compare_mir_test_output(&self, test_name: &str, expected_content: &Vec<&str>) {
+    fn load_mir_dump(&self, test_name: &str) -> String {
         let mut output_file = PathBuf::new();
         output_file.push(self.get_mir_dump_dir());
         output_file.push(test_name);
         debug!("comparing the contests of: {:?}", output_file);
-        debug!("with: {:?}", expected_content);
         self.check_mir_test_timestamp(test_name, &output_file);
 
         let mut dumped_file = fs::File::open(output_file.clone()).unwrap();
         let mut dumped_string = String::new();
         dumped_file.read_to_string(&mut dumped_string).unwrap();
-        let mut dumped_lines = dumped_string.lines().filter(|l| !l.is_empty());
-        let mut expected_lines = expected_content.iter().filter(|l| !l.is_empty());
-
-        // We expect each non-empty line from expected_content to appear
-        // in the dump in order, but there may be extra lines interleaved
-        while let Some(expected_line) = expected_lines.next() {
-            let e_norm = normalize_mir_line(expected_line);
-            if e_norm.is_empty() {
-                continue;
-            };
-            let mut found = false;
-            while let Some(dumped_line) = dumped_lines.next() {
-                let d_norm = normalize_mir_line(dumped_line);
-                debug!("found: {:?}", d_norm);
-                debug!("expected: {:?}", e_norm);
-                if e_norm == d_norm {
-                    found = true;
-                    break;
-                };
-            }
-            if !found {
-                let normalize_all = dumped_string.lines()
-                                                 .map(nocomment_mir_line)
-                                                 .filter(|l| !l.is_empty())
-                                                 .collect::<Vec<_>>()
-                                                 .join("\n");
-                panic!("ran out of mir dump output to match against.\n\
-                        Did not find expected line: {:?}\n\
-                        Expected:\n{}\n\
-                        Actual:\n{}",
-                        expected_line,
-                        expected_content.join("\n"),
-                        normalize_all);
-            }
-        }
+        dumped_string
     }
 
     fn get_mir_dump_dir(&self) -> PathBuf {
@@ -2561,7 +2583,12 @@ actual:\n\
         }
     }
 
-    fn compare_output(&self, kind: &str, actual: &str, expected: &str) -> usize {
+    fn compare_output(&self, kind: &str, actual: &str, expected_path: &Path, expected: &str)
+                      -> usize {
+        if self.config.bless {
+            self.bless_output(kind, actual, expected_path, expected);
+            return 0;
+        }
         if actual == expected {
             return 0;
         }
@@ -2586,6 +2613,29 @@ actual:\n\
         println!("Actual {} saved to {}", kind, output_file.display());
         1
     }
+
+    // Makes the actual output the expected one, deleting the reference file
+    // altogether when there is no output.
+    fn bless_output(&self, kind: &str, actual: &str, expected_path: &Path, expected: &str) {
+        let result = if actual.is_empty() {
+            if !expected_path.exists() {
+                return;
+            }
+            fs::remove_file(expected_path)
+        } else {
+            if actual == expected {
+                return;
+            }
+            File::create(expected_path).and_then(|mut f| f.write_all(actual.as_bytes()))
+        };
+        match result {
+            Ok(()) => println!("blessed {} in {}", kind, expected_path.display()),
+            Err(e) => {
+                self.fatal(&format!("failed to bless {} in `{}`: {}",
+                                    kind, expected_path.display(), e))
+            }
+        }
+    }
 }
 
 struct ProcArgs {
@@ -2658,6 +2708,47 @@ enum TargetLocation {
     ThisDirectory(PathBuf),
 }
 
+// Checks that each non-empty line of `expected_content` appears in the dumped
+// MIR, in order, though there may be extra lines interleaved.
+fn compare_mir_test_output(expected_content: &[&str], dumped_string: &str)
+                           -> Result<(), String> {
+    debug!("with: {:?}", expected_content);
+    let mut dumped_lines = dumped_string.lines().filter(|l| !l.is_empty());
+    let mut expected_lines = expected_content.iter().filter(|l| !l.is_empty());
+
+    while let Some(expected_line) = expected_lines.next() {
+        let e_norm = normalize_mir_line(expected_line);
+        if e_norm.is_empty() {
+            continue;
+        };
+        let mut found = false;
+        while let Some(dumped_line) = dumped_lines.next() {
+            let d_norm = normalize_mir_line(dumped_line);
+            debug!("found: {:?}", d_norm);
+            debug!("expected: {:?}", e_norm);
+            if e_norm == d_norm {
+                found = true;
+                break;
+            };
+        }
+        if !found {
+            let normalize_all = dumped_string.lines()
+                                             .map(nocomment_mir_line)
+                                             .filter(|l| !l.is_empty())
+                                             .collect::<Vec<_>>()
+                                             .join("\n");
+            return Err(format!("ran out of mir dump output to match against.\n\
+                                Did not find expected line: {:?}\n\
+                                Expected:\n{}\n\
+                                Actual:\n{}",
+                               expected_line,
+                               expected_content.join("\n"),
+                               normalize_all));
+        }
+    }
+    Ok(())
+}
+
 fn normalize_mir_line(line: &str) -> String {
     nocomment_mir_line(line).replace(char::is_whitespace, "")
 }